@@ -2,10 +2,17 @@
 
 extern crate test;
 
-use growable::{GrowablePoolBuilder, Reusable};
-use std::{collections::VecDeque, fmt::Debug};
+use growable::{Growable, GrowablePoolBuilder, Reusable, SelectionPolicy};
+use std::{
+    alloc::{self, Layout},
+    collections::VecDeque,
+    fmt::Debug,
+    ptr,
+};
 use test::Bencher;
 
+const KIB: usize = 1024;
+
 #[bench]
 fn bench_box(bencher: &mut Bencher) {
     let mut buffer: VecDeque<Box<dyn Debug>> = VecDeque::with_capacity(1024);
@@ -48,3 +55,114 @@ fn bench_growable(bencher: &mut Bencher) {
         }
     });
 }
+
+/// Writes the whole block, making sure the stores are not optimized away.
+unsafe fn fill(ptr: *mut u8, layout: Layout) {
+    ptr::write_bytes(ptr, 0, layout.size());
+    test::black_box(ptr);
+}
+
+#[bench]
+fn bench_regrow_large_realloc(bencher: &mut Bencher) {
+    // Mimics a regrowth through `realloc` which has to copy the old contents.
+    bencher.iter(|| unsafe {
+        let mut layout = Layout::from_size_align(64 * KIB, 8).unwrap();
+        let mut ptr = alloc::alloc(layout);
+        fill(ptr, layout);
+        for size in [128 * KIB, 256 * KIB] {
+            ptr = alloc::realloc(ptr, layout, size);
+            layout = Layout::from_size_align(size, 8).unwrap();
+            fill(ptr, layout);
+        }
+        alloc::dealloc(test::black_box(ptr), layout);
+    });
+}
+
+#[bench]
+fn bench_regrow_large_dealloc_alloc(bencher: &mut Bencher) {
+    // Mimics a regrowth which drops the old contents, the baseline for a `Growable`.
+    bencher.iter(|| unsafe {
+        let mut layout = Layout::from_size_align(64 * KIB, 8).unwrap();
+        let mut ptr = alloc::alloc(layout);
+        fill(ptr, layout);
+        for size in [128 * KIB, 256 * KIB] {
+            alloc::dealloc(ptr, layout);
+            layout = Layout::from_size_align(size, 8).unwrap();
+            ptr = alloc::alloc(layout);
+            fill(ptr, layout);
+        }
+        alloc::dealloc(test::black_box(ptr), layout);
+    });
+}
+
+#[bench]
+fn bench_regrow_large_growable(bencher: &mut Bencher) {
+    // NB: Values are initialized in place, a large array on the stack would be mostly memcpy.
+    fn regrow<const N: usize>(growable: Growable) -> Growable {
+        let mut uninit = growable.consume_uninit::<[u64; N]>();
+        unsafe { fill(uninit.as_mut_ptr().cast(), Layout::new::<[u64; N]>()) };
+        Reusable::free(uninit)
+    }
+    // --
+    bencher.iter(|| {
+        let growable = Growable::with_capacity(64 * KIB, 8);
+        let growable = regrow::<{ 64 * KIB / 8 }>(growable);
+        let growable = regrow::<{ 128 * KIB / 8 }>(growable);
+        let growable = regrow::<{ 256 * KIB / 8 }>(growable);
+        test::black_box(growable)
+    });
+}
+
+fn bench_selection_policy(bencher: &mut Bencher, selection_policy: SelectionPolicy) {
+    let mut buffer: Vec<Reusable<dyn Debug>> = Vec::with_capacity(64);
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(16)
+        .with_default_ptr_alignment(8)
+        .with_capacity(64)
+        .with_selection_policy(selection_policy)
+        .build();
+    let mut seed = 0usize;
+    let mut round = || {
+        for i in 0 .. 64 {
+            let item: Reusable<dyn Debug> = match i % 4 {
+                0 => pool.allocate([0u8; 16]),
+                1 => pool.allocate([0u8; 256]),
+                2 => pool.allocate([0u8; 1024]),
+                3 => pool.allocate([0u8; 4096]),
+                _ => unreachable!(),
+            };
+            buffer.push(item);
+        }
+        // Return blocks in a scrambled but deterministic order.
+        while !buffer.is_empty() {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let i = (seed >> 33) % buffer.len();
+            pool.free(buffer.swap_remove(i));
+        }
+    };
+    // Blocks only ever grow, see `pool_selection_policy_reallocations` for the allocation counts.
+    for _ in 0 .. 4 {
+        round();
+    }
+    bencher.iter(round);
+}
+
+#[bench]
+fn bench_selection_lifo(bencher: &mut Bencher) {
+    bench_selection_policy(bencher, SelectionPolicy::Lifo);
+}
+
+#[bench]
+fn bench_selection_fifo(bencher: &mut Bencher) {
+    bench_selection_policy(bencher, SelectionPolicy::Fifo);
+}
+
+#[bench]
+fn bench_selection_first_fit(bencher: &mut Bencher) {
+    bench_selection_policy(bencher, SelectionPolicy::FirstFit);
+}
+
+#[bench]
+fn bench_selection_best_fit(bencher: &mut Bencher) {
+    bench_selection_policy(bencher, SelectionPolicy::BestFit);
+}
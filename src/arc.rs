@@ -0,0 +1,457 @@
+use crate::{
+    rc::MAX_REFCOUNT,
+    shared::{free_to, PoolHook},
+    sync::{
+        atomic::{self, AtomicUsize},
+        hint,
+    },
+    Growable, Reusable,
+};
+use std::{
+    alloc::{Allocator, Global},
+    fmt,
+    marker::{PhantomData, Unsize},
+    mem::{self, ManuallyDrop},
+    ops::{self, CoerceUnsized},
+    process,
+    ptr::{self, NonNull},
+    sync::atomic::Ordering,
+};
+
+/// The memory layout of a [`ReusableArc`], the header lives in the same [`Growable`] as the value.
+#[repr(C)]
+pub(crate) struct ArcBox<T: ?Sized, A: Allocator + Clone> {
+    strong: AtomicUsize,
+    weak: AtomicUsize,
+    growable: ManuallyDrop<Growable<A>>,
+    pool: PoolHook<A>,
+    value: T,
+}
+
+/// A thread-safe reference-counted [`Reusable`], the reference counts are kept
+/// within the [`Growable`] along with the value.
+///
+/// # Notes
+///
+/// Once the last reference is gone the memory goes back to the [`SyncGrowablePool`]
+/// it was allocated from with [`SyncGrowablePool::allocate_arc`], it is deallocated otherwise.
+/// Use [`ReusableArc::try_unwrap`] or [`ReusableArc::into_growable`] to fetch it back manually.
+///
+/// Unlike the standard library counterpart, a [`WeakReusableArc`] keeps the whole memory
+/// block alive rather than just the reference counts. As long as any weak reference exists
+/// [`ReusableArc::try_unwrap`] fails even for the last strong reference and
+/// [`ReusableArc::into_growable`] drops the value but returns nothing, the memory then goes back
+/// to the pool (or is deallocated) once the last weak reference is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::{sync::Arc, thread};
+///   let pool = Arc::new(SyncGrowablePool::new());
+///   let arc = pool.allocate_arc(42u32);
+///   let other = ReusableArc::clone(&arc);
+///   thread::spawn(move || assert_eq!(*other, 42)).join().unwrap();
+///   drop(arc);
+///   assert_eq!(pool.len(), 1);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`Reusable`]: struct.Reusable.html
+/// [`ReusableArc::into_growable`]: struct.ReusableArc.html#method.into_growable
+/// [`ReusableArc::try_unwrap`]: struct.ReusableArc.html#method.try_unwrap
+/// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+/// [`SyncGrowablePool::allocate_arc`]: struct.SyncGrowablePool.html#method.allocate_arc
+/// [`WeakReusableArc`]: struct.WeakReusableArc.html
+pub struct ReusableArc<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<ArcBox<T, A>>,
+    phantom: PhantomData<ArcBox<T, A>>,
+}
+
+/// A weak reference to a [`ReusableArc`], it keeps the memory but not the value alive.
+///
+/// [`ReusableArc`]: struct.ReusableArc.html
+pub struct WeakReusableArc<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<ArcBox<T, A>>,
+}
+
+unsafe impl<T, A> Send for ReusableArc<T, A>
+where
+    T: Send + Sync + ?Sized,
+    A: Allocator + Clone + Send + Sync,
+{
+}
+
+unsafe impl<T, A> Sync for ReusableArc<T, A>
+where
+    T: Send + Sync + ?Sized,
+    A: Allocator + Clone + Send + Sync,
+{
+}
+
+unsafe impl<T, A> Send for WeakReusableArc<T, A>
+where
+    T: Send + Sync + ?Sized,
+    A: Allocator + Clone + Send + Sync,
+{
+}
+
+unsafe impl<T, A> Sync for WeakReusableArc<T, A>
+where
+    T: Send + Sync + ?Sized,
+    A: Allocator + Clone + Send + Sync,
+{
+}
+
+impl<T, A> Clone for ReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        if self.inner().strong.fetch_add(1, Ordering::Relaxed) >= MAX_REFCOUNT {
+            process::abort();
+        }
+        ReusableArc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A> ops::Deref for ReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().value
+    }
+}
+
+impl<T, A> fmt::Pointer for ReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), formatter)
+    }
+}
+
+impl<T, A> fmt::Debug for ReusableArc<T, A>
+where
+    T: fmt::Debug + ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Drop for ReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some((growable, pool)) = unsafe { self.release() } {
+            free_to(pool, growable);
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<ReusableArc<U, A>> for ReusableArc<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> ReusableArc<T, A>
+where
+    A: Allocator + Clone,
+{
+    /// Moves the value into the `Growable` along with the reference counts.
+    pub(crate) fn new_in(growable: Growable<A>, t: T, pool: PoolHook<A>) -> Self {
+        let (ptr, growable) = Reusable::into_raw_parts(growable.consume_uninit::<ArcBox<T, A>>());
+        let ptr = ptr.cast::<ArcBox<T, A>>();
+        unsafe {
+            ptr.as_ptr().write(ArcBox {
+                strong: AtomicUsize::new(1),
+                weak: AtomicUsize::new(1),
+                growable: ManuallyDrop::new(growable),
+                pool,
+                value: t,
+            });
+        }
+        ReusableArc {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A> ReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Creates a new [`WeakReusableArc`] to the same value.
+    ///
+    /// [`WeakReusableArc`]: struct.WeakReusableArc.html
+    pub fn downgrade(this: &Self) -> WeakReusableArc<T, A> {
+        let weak = &this.inner().weak;
+        let mut count = weak.load(Ordering::Relaxed);
+        loop {
+            // NB: The weak count is locked while `is_unique` checks the strong count.
+            if count == usize::MAX {
+                hint::spin_loop();
+                count = weak.load(Ordering::Relaxed);
+                continue;
+            }
+            if count >= MAX_REFCOUNT {
+                process::abort();
+            }
+            match weak.compare_exchange_weak(count, count + 1, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => {
+                    return WeakReusableArc {
+                        ptr: this.ptr,
+                    }
+                },
+                Err(actual) => count = actual,
+            }
+        }
+    }
+
+    /// Returns the number of strong references to the value.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of weak references to the value.
+    pub fn weak_count(this: &Self) -> usize {
+        match this.inner().weak.load(Ordering::Relaxed) {
+            usize::MAX => 0,
+            count => count - 1,
+        }
+    }
+
+    /// Returns `true` if both references point to the same value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    /// Returns a mutable reference to the value if there are no other references to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Self::is_unique(this) {
+            Some(unsafe { &mut (*this.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a [`Reusable`] if there are no other references to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same `ReusableArc` back if there are other strong or weak references.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let arc = Growable::new().consume_arc(42u32);
+    ///   let other = ReusableArc::clone(&arc);
+    ///   let arc = ReusableArc::try_unwrap(arc).unwrap_err();
+    ///   drop(other);
+    ///   let num = ReusableArc::try_unwrap(arc).unwrap();
+    ///   assert_eq!(*num, 42);
+    /// ```
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn try_unwrap(this: Self) -> Result<Reusable<T, A>, Self> {
+        if !Self::is_unique(&this) {
+            return Err(this);
+        }
+        let this = ManuallyDrop::new(this);
+        unsafe {
+            let inner = this.ptr.as_ptr();
+            let growable = ManuallyDrop::take(&mut (*inner).growable);
+            drop(ptr::read(&(*inner).pool));
+            // NB: The header goes away, the value is moved to the beginning of the `Growable`.
+            let value = ptr::addr_of_mut!((*inner).value);
+            let start = growable.ptr.as_ptr();
+            ptr::copy(value.cast::<u8>(), start, mem::size_of_val(&*value));
+            let ptr = NonNull::new_unchecked(ptr::from_raw_parts_mut(start, ptr::metadata(value)));
+            Ok(growable.into_reusable(ptr))
+        }
+    }
+
+    /// Drops this reference, returning the memory back as a [`Growable`]
+    /// if it was the last one.
+    ///
+    /// # Notes
+    ///
+    /// The value is dropped along with the last strong reference, the memory is returned
+    /// only if there are no weak references left as well.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn into_growable(this: Self) -> Option<Growable<A>> {
+        let mut this = ManuallyDrop::new(this);
+        unsafe { this.release() }.map(|(growable, _)| growable)
+    }
+
+    fn is_unique(this: &Self) -> bool {
+        let inner = this.inner();
+        // NB: Locks the weak count so that no weak reference is created in the meantime.
+        if inner.weak.compare_exchange(1, usize::MAX, Ordering::Acquire, Ordering::Relaxed).is_err()
+        {
+            return false;
+        }
+        let unique = inner.strong.load(Ordering::Acquire) == 1;
+        inner.weak.store(1, Ordering::Release);
+        unique
+    }
+
+    fn inner(&self) -> &ArcBox<T, A> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Drops this strong reference, returning the memory if it was the last reference.
+    unsafe fn release(&mut self) -> Option<(Growable<A>, PoolHook<A>)> {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return None;
+        }
+        atomic::fence(Ordering::Acquire);
+        ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr.as_ptr()).value));
+        // NB: The strong references hold a single weak reference all together.
+        release_weak(self.ptr)
+    }
+}
+
+impl<T, A> Clone for WeakReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        // NB: Aborting long before `usize::MAX` keeps the count clear of the lock value.
+        if unsafe { self.ptr.as_ref() }.weak.fetch_add(1, Ordering::Relaxed) >= MAX_REFCOUNT {
+            process::abort();
+        }
+        WeakReusableArc {
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T, A> fmt::Debug for WeakReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "(Weak)")
+    }
+}
+
+impl<T, A> Drop for WeakReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some((growable, pool)) = unsafe { release_weak(self.ptr) } {
+            free_to(pool, growable);
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<WeakReusableArc<U, A>> for WeakReusableArc<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> WeakReusableArc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Returns a strong reference to the value if it is still alive.
+    pub fn upgrade(&self) -> Option<ReusableArc<T, A>> {
+        let strong = &unsafe { self.ptr.as_ref() }.strong;
+        let mut count = strong.load(Ordering::Relaxed);
+        while count != 0 {
+            if count >= MAX_REFCOUNT {
+                process::abort();
+            }
+            match strong.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return Some(ReusableArc {
+                        ptr: self.ptr,
+                        phantom: PhantomData,
+                    })
+                },
+                Err(actual) => count = actual,
+            }
+        }
+        None
+    }
+
+    /// Returns the number of strong references to the value.
+    pub fn strong_count(&self) -> usize {
+        unsafe { self.ptr.as_ref() }.strong.load(Ordering::Relaxed)
+    }
+}
+
+impl<A> Growable<A>
+where
+    A: Allocator + Clone,
+{
+    /// Places the value into a [`ReusableArc`], reusing the memory of this `Growable`.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let arc = Growable::new().consume_arc(42u32);
+    ///   assert_eq!(*arc, 42);
+    /// ```
+    ///
+    /// [`ReusableArc`]: struct.ReusableArc.html
+    #[inline]
+    pub fn consume_arc<T>(self, t: T) -> ReusableArc<T, A> {
+        ReusableArc::new_in(self, t, None)
+    }
+}
+
+/// Drops a weak reference, returning the memory if it was the last reference.
+unsafe fn release_weak<T, A>(ptr: NonNull<ArcBox<T, A>>) -> Option<(Growable<A>, PoolHook<A>)>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    let inner = ptr.as_ptr();
+    if (*inner).weak.fetch_sub(1, Ordering::Release) != 1 {
+        return None;
+    }
+    atomic::fence(Ordering::Acquire);
+    Some((ManuallyDrop::take(&mut (*inner).growable), ptr::read(&(*inner).pool)))
+}
@@ -0,0 +1,262 @@
+use crate::{Growable, GrowthPolicy, Reusable, ReusableString, ReusableVec, SyncGrowablePool};
+use std::{
+    alloc::{Allocator, Layout},
+    fmt,
+    mem::{self, MaybeUninit},
+};
+
+/// A thread-local cache of [`Growable`] objects on top of a [`SyncGrowablePool`].
+///
+/// Objects are kept in two magazines which are refilled from and flushed back to the pool
+/// in batches, so most allocations never touch the pool lock at all.
+///
+/// # Notes
+///
+/// A [`Reusable`] carries no reference to the pool it came from, so it is fine to allocate it
+/// with one cache and free it with another one on a different thread (or directly to the pool).
+///
+/// Objects held by a cache are not available to other threads and are not taken into account
+/// by the pool capacity until the cache is flushed or dropped.
+///
+/// Every object moved from the pool into a cache is counted as an allocation in the pool
+/// statistics and every object moved back is counted as a free.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::thread;
+///   let pool = GrowablePool::builder().with_magazine_capacity(8).build_shared();
+///   thread::scope(|scope| {
+///       for i in 0 .. 4 {
+///           let pool = &pool;
+///           scope.spawn(move || {
+///               let mut cache = pool.cache();
+///               for j in 0 .. 64 {
+///                   let num = cache.allocate(i * j);
+///                   assert_eq!(*num, i * j);
+///                   cache.free(num);
+///               }
+///           });
+///       }
+///   });
+///   assert!(!pool.is_empty());
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`Reusable`]: struct.Reusable.html
+/// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+pub struct GrowableCache<'pool, A: Allocator + Clone> {
+    pool: &'pool SyncGrowablePool<A>,
+    growth_policy: GrowthPolicy,
+    loaded: Vec<Growable<A>>,
+    previous: Vec<Growable<A>>,
+}
+
+impl<A> fmt::Debug for GrowableCache<'_, A>
+where
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "GrowableCache {{ .. {} more allocations available .. }}", self.len())
+    }
+}
+
+impl<A> Drop for GrowableCache<'_, A>
+where
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl<'pool, A> GrowableCache<'pool, A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new empty cache on top of the pool.
+    pub(crate) fn new(pool: &'pool SyncGrowablePool<A>) -> Self {
+        let magazine_len = pool.magazine_len();
+        GrowableCache {
+            pool,
+            growth_policy: pool.lock().growth_policy,
+            loaded: Vec::with_capacity(magazine_len),
+            previous: Vec::with_capacity(magazine_len),
+        }
+    }
+
+    /// Returns the pool this cache is backed by.
+    #[inline]
+    pub fn pool(&self) -> &'pool SyncGrowablePool<A> {
+        self.pool
+    }
+
+    /// Returns true if the pool will be accessed to allocate an another one object.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the current amount of allocations that this cache can provide
+    /// without accessing the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.loaded.len() + self.previous.len()
+    }
+
+    /// Allocates a new [`Reusable`] from the cache.
+    ///
+    /// # Notes
+    ///
+    /// If the cache is empty, a magazine is refilled from the pool.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate<T>(&mut self, t: T) -> Reusable<T, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume(t)
+    }
+
+    /// Allocates a new [`Reusable`] from the cache, storing the value returned
+    /// by the given closure.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_with<T, F>(&mut self, f: F) -> Reusable<T, A>
+    where
+        F: FnOnce() -> T,
+    {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_with(f)
+    }
+
+    /// Allocates a new [`Reusable`] from the cache with an uninitialized value.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_uninit<T>(&mut self) -> Reusable<MaybeUninit<T>, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_uninit()
+    }
+
+    /// Allocates a new [`Reusable`] from the cache, moving an unsized value out of the [`Box`].
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_box<T, B>(&mut self, b: Box<T, B>) -> Reusable<T, A>
+    where
+        T: ?Sized,
+        B: Allocator,
+    {
+        let layout = Layout::for_value::<T>(&b);
+        self.take(layout.size(), layout.align()).consume_box(b)
+    }
+
+    /// Allocates a new [`Reusable`] from the cache, cloning the slice into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_clone<T>(&mut self, slice: &[T]) -> Reusable<[T], A>
+    where
+        T: Clone,
+    {
+        let layout = Layout::for_value(slice);
+        self.take(layout.size(), layout.align()).consume_slice_clone(slice)
+    }
+
+    /// Allocates a new [`Reusable`] from the cache, collecting the iterator into a slice.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_from_iter<T, I>(&mut self, iter: I) -> Reusable<[T], A>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len().saturating_mul(mem::size_of::<T>());
+        self.take(len, mem::align_of::<T>()).consume_slice_from_iter(iter)
+    }
+
+    /// Allocates a new [`Reusable`] from the cache, copying the string into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_str(&mut self, s: &str) -> Reusable<str, A> {
+        self.take(s.len(), 1).consume_str(s)
+    }
+
+    /// Allocates a new empty [`ReusableVec`] from the cache.
+    ///
+    /// [`ReusableVec`]: struct.ReusableVec.html
+    #[inline]
+    pub fn allocate_vec<T>(&mut self) -> ReusableVec<T, A> {
+        ReusableVec::from_growable(self.take(0, 1))
+    }
+
+    /// Allocates a new empty [`ReusableString`] from the cache.
+    ///
+    /// [`ReusableString`]: struct.ReusableString.html
+    #[inline]
+    pub fn allocate_string(&mut self) -> ReusableString<A> {
+        ReusableString::from_growable(self.take(0, 1))
+    }
+
+    /// Returns the [`Reusable`] back to the cache, marking it
+    /// available for a next allocation.
+    ///
+    /// # Notes
+    ///
+    /// If both magazines are full, the older one is flushed back to the pool.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn free<G>(&mut self, t: G)
+    where
+        G: Into<Growable<A>>,
+    {
+        let mut growable = t.into();
+        growable.set_growth_policy(self.growth_policy);
+        if self.loaded.len() >= self.pool.magazine_len() {
+            if !self.previous.is_empty() {
+                self.pool.lock().free_batch(self.previous.drain(..));
+            }
+            mem::swap(&mut self.loaded, &mut self.previous);
+        }
+        self.loaded.push(growable);
+    }
+
+    /// Returns every cached [`Growable`] back to the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn flush(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        self.pool.lock().free_batch(self.previous.drain(..).chain(self.loaded.drain(..)));
+    }
+
+    /// Takes a [`Growable`] from the cache to store `len` bytes with the given alignment,
+    /// refilling a magazine from the pool if the cache is empty.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn take(&mut self, len: usize, ptr_alignment: usize) -> Growable<A> {
+        if self.loaded.is_empty() {
+            mem::swap(&mut self.loaded, &mut self.previous);
+        }
+        if self.loaded.is_empty() {
+            let magazine_len = self.pool.magazine_len();
+            let growable =
+                self.pool.lock().take_batch(len, ptr_alignment, &mut self.loaded, magazine_len);
+            // NB: The pool is drained from the most recently returned one,
+            // keep it on top of the magazine.
+            self.loaded.reverse();
+            return growable;
+        }
+        let position = self
+            .loaded
+            .iter()
+            .rposition(|growable| growable.fits(len, ptr_alignment))
+            .unwrap_or(self.loaded.len() - 1);
+        self.loaded.remove(position)
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{EvictionPolicy, Growable, SelectionPolicy};
+use std::{
+    alloc::Allocator,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    iter,
+};
+
+/// A list of free [`Growable`] objects segregated into buckets by size classes.
+///
+/// A bucket `i` serves requests of up to `size_classes[i]` bytes and stores blocks that are
+/// at least that large (but smaller than the next size class). The last bucket serves and stores
+/// everything larger than the largest size class, so without size classes there is a single one.
+///
+/// Blocks are also indexed by their stamps and lengths to pick eviction victims without
+/// scanning the buckets.
+pub(crate) struct FreeList<A: Allocator> {
+    size_classes: Vec<usize>,
+    buckets: Vec<VecDeque<Entry<A>>>,
+    ages: BTreeMap<u64, (usize, usize)>,
+    sizes: BTreeSet<(usize, u64)>,
+    len: usize,
+    bytes: usize,
+    clock: u64,
+}
+
+/// The position of a removed block along with its stamp, enough to insert it back as it was.
+pub(crate) type Slot = ((usize, usize), u64);
+
+/// A free block along with the moment it was filed.
+struct Entry<A: Allocator> {
+    growable: Growable<A>,
+    stamp: u64,
+}
+
+impl<A> FreeList<A>
+where
+    A: Allocator,
+{
+    /// Creates an empty list, size classes must be sorted and deduplicated.
+    pub(crate) fn new(size_classes: &[usize]) -> Self {
+        FreeList {
+            size_classes: size_classes.to_vec(),
+            buckets: (0 ..= size_classes.len()).map(|_| VecDeque::new()).collect(),
+            ages: BTreeMap::new(),
+            sizes: BTreeSet::new(),
+            len: 0,
+            bytes: 0,
+            clock: 0,
+        }
+    }
+
+    /// Returns the total amount of blocks.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the total capacity of all blocks.
+    #[inline]
+    pub(crate) fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the size classes.
+    #[inline]
+    pub(crate) fn size_classes(&self) -> &[usize] {
+        &self.size_classes
+    }
+
+    /// Returns the size class of the bucket, if any.
+    #[inline]
+    pub(crate) fn size_class(&self, bucket: usize) -> Option<usize> {
+        self.size_classes.get(bucket).copied()
+    }
+
+    /// Returns the bucket serving requests of `len` bytes.
+    #[inline]
+    pub(crate) fn bucket_for_request(&self, len: usize) -> usize {
+        self.size_classes.partition_point(|&size_class| size_class < len)
+    }
+
+    /// Returns the bucket storing blocks of `len` bytes.
+    #[inline]
+    pub(crate) fn bucket_for_block(&self, len: usize) -> usize {
+        match self.size_classes.partition_point(|&size_class| size_class <= len) {
+            // Too small to serve anything without a regrowth, still better than nothing.
+            0 => 0,
+            n if n == self.size_classes.len() && self.size_classes[n - 1] < len => n,
+            n => n - 1,
+        }
+    }
+
+    /// Files the block into the matching bucket.
+    #[inline]
+    pub(crate) fn push(&mut self, growable: Growable<A>, selection_policy: SelectionPolicy) {
+        let bucket = self.bucket_for_block(growable.len());
+        self.push_into(bucket, growable, selection_policy);
+    }
+
+    /// Files the block into the given bucket.
+    #[inline]
+    pub(crate) fn push_into(
+        &mut self,
+        bucket: usize,
+        growable: Growable<A>,
+        selection_policy: SelectionPolicy,
+    ) {
+        self.clock += 1;
+        let entry = self.index(bucket, growable, self.clock);
+        match selection_policy {
+            SelectionPolicy::Fifo => self.buckets[bucket].push_back(entry),
+            _ => self.buckets[bucket].push_front(entry),
+        }
+    }
+
+    /// Files every block of the batch into the matching bucket.
+    pub(crate) fn push_batch<I>(&mut self, batch: I, selection_policy: SelectionPolicy)
+    where
+        I: IntoIterator<Item = Growable<A>>,
+    {
+        for growable in batch {
+            self.push(growable, selection_policy);
+        }
+    }
+
+    /// Removes up to `count` blocks from the front of buckets starting with the smallest one,
+    /// appending them to the batch.
+    pub(crate) fn pop_batch(&mut self, count: usize, batch: &mut Vec<Growable<A>>) {
+        let mut stamps = Vec::with_capacity(count);
+        for bucket in &mut self.buckets {
+            let len = std::cmp::min(count - stamps.len(), bucket.len());
+            batch.extend(bucket.drain(.. len).map(|entry| {
+                stamps.push(entry.stamp);
+                entry.growable
+            }));
+        }
+        for stamp in stamps {
+            self.unindex(stamp);
+        }
+    }
+
+    /// Inserts the block back to the position it was removed from with the original stamp,
+    /// so it does not look more recently returned than it is.
+    #[inline]
+    pub(crate) fn insert(
+        &mut self,
+        (bucket, position): (usize, usize),
+        growable: Growable<A>,
+        stamp: u64,
+    ) {
+        let entry = self.index(bucket, growable, stamp);
+        self.buckets[bucket].insert(position, entry);
+    }
+
+    /// Removes the block at the given position.
+    #[inline]
+    pub(crate) fn remove(&mut self, position: (usize, usize)) -> Option<Growable<A>> {
+        self.remove_stamped(position).map(|(growable, _)| growable)
+    }
+
+    /// Removes the block at the given position along with its stamp.
+    #[inline]
+    pub(crate) fn remove_stamped(
+        &mut self,
+        (bucket, position): (usize, usize),
+    ) -> Option<(Growable<A>, u64)> {
+        let Entry {
+            growable,
+            stamp,
+        } = self.buckets[bucket].remove(position)?;
+        self.unindex(stamp);
+        Some((growable, stamp))
+    }
+
+    /// Accounts for a block being filed into the bucket.
+    #[inline]
+    fn index(&mut self, bucket: usize, growable: Growable<A>, stamp: u64) -> Entry<A> {
+        self.len += 1;
+        self.bytes += growable.len;
+        self.ages.insert(stamp, (bucket, growable.len));
+        self.sizes.insert((growable.len, stamp));
+        Entry {
+            growable,
+            stamp,
+        }
+    }
+
+    /// Accounts for a block being removed, returning its bucket.
+    #[inline]
+    fn unindex(&mut self, stamp: u64) -> Option<usize> {
+        let (bucket, len) = self.ages.remove(&stamp)?;
+        self.sizes.remove(&(len, stamp));
+        self.len -= 1;
+        self.bytes -= len;
+        Some(bucket)
+    }
+
+    /// Removes the least recently filed blocks until at most `len` of them holding at most
+    /// `bytes` bytes are left.
+    pub(crate) fn trim(&mut self, len: usize, bytes: usize) {
+        let (mut count, mut total) = (self.len, self.bytes);
+        let victims: Vec<_> = self
+            .ages
+            .iter()
+            .take_while(|&(_, &(_, block_len))| {
+                if count <= len && total <= bytes {
+                    return false;
+                }
+                count -= 1;
+                total -= block_len;
+                true
+            })
+            .map(|(&stamp, _)| stamp)
+            .collect();
+        self.remove_all(victims);
+    }
+
+    /// Returns stamps and lengths of blocks that might be dropped to make room for a block of
+    /// `len` bytes according to the eviction policy, the most preferred first.
+    pub(crate) fn victims(
+        &self,
+        len: usize,
+        eviction_policy: EvictionPolicy,
+    ) -> Box<dyn Iterator<Item = (u64, usize)> + '_> {
+        match eviction_policy {
+            EvictionPolicy::DropIncoming => Box::new(iter::empty()),
+            EvictionPolicy::DropSmallest => {
+                Box::new(self.sizes.range(.. (len, 0)).map(|&(len, stamp)| (stamp, len)))
+            },
+            EvictionPolicy::DropLargest => {
+                // NB: Larger blocks go first but among blocks of the same length
+                // the least recently filed one is still preferred.
+                let next_len = |&above: &usize| {
+                    self.sizes.range(.. (above, 0)).next_back().map(|&(len, _)| len)
+                };
+                let lens = iter::successors(self.sizes.last().map(|&(len, _)| len), next_len);
+                Box::new(
+                    lens.take_while(move |&block_len| block_len > len)
+                        .flat_map(|len| self.sizes.range((len, 0) ..= (len, u64::MAX)))
+                        .map(|&(len, stamp)| (stamp, len)),
+                )
+            },
+            EvictionPolicy::DropLeastRecentlyUsed => {
+                Box::new(self.ages.iter().map(|(&stamp, &(_, len))| (stamp, len)))
+            },
+        }
+    }
+
+    /// Removes blocks with the given stamps in a single pass over the affected buckets.
+    pub(crate) fn remove_all(&mut self, mut stamps: Vec<u64>) {
+        let mut affected = vec![false; self.buckets.len()];
+        for &stamp in &stamps {
+            if let Some(bucket) = self.unindex(stamp) {
+                affected[bucket] = true;
+            }
+        }
+        stamps.sort_unstable();
+        for (bucket, vec) in self.buckets.iter_mut().enumerate() {
+            if affected[bucket] {
+                vec.retain(|entry| stamps.binary_search(&entry.stamp).is_err());
+            }
+        }
+    }
+
+    /// Releases the memory held by the buckets themselves as much as possible.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.shrink_to_fit();
+        }
+    }
+
+    /// Returns the position of a block to store `len` bytes with the given alignment according
+    /// to the selection policy, looking into the first non-empty bucket large enough.
+    pub(crate) fn select(
+        &self,
+        len: usize,
+        ptr_alignment: usize,
+        selection_policy: SelectionPolicy,
+    ) -> Option<(usize, usize)> {
+        let bucket = (self.bucket_for_request(len) .. self.buckets.len())
+            .find(|&bucket| !self.buckets[bucket].is_empty())?;
+        let vec = &self.buckets[bucket];
+        let mut fitting =
+            vec.iter().enumerate().filter(|(_, entry)| entry.growable.fits(len, ptr_alignment));
+        let position = match selection_policy {
+            SelectionPolicy::Lifo | SelectionPolicy::Fifo => 0,
+            SelectionPolicy::FirstFit => fitting.next().map_or(0, |(position, _)| position),
+            SelectionPolicy::BestFit => {
+                match fitting.min_by_key(|(_, entry)| entry.growable.len()) {
+                    Some((position, _)) => position,
+                    None => {
+                        // NB: max_by_key returns the last maximum, iterating in reverse
+                        // makes the most recently returned one preferred.
+                        vec.iter()
+                            .enumerate()
+                            .rev()
+                            .max_by_key(|(_, entry)| entry.growable.len())
+                            .map_or(0, |(position, _)| position)
+                    },
+                }
+            },
+        };
+        Some((bucket, position))
+    }
+
+    /// Returns the position of the first block capable to store `len` bytes with
+    /// the given alignment.
+    pub(crate) fn find(&self, len: usize, ptr_alignment: usize) -> Option<(usize, usize)> {
+        (self.bucket_for_request(len) .. self.buckets.len()).find_map(|bucket| {
+            let position = self.buckets[bucket]
+                .iter()
+                .position(|entry| entry.growable.fits(len, ptr_alignment))?;
+            Some((bucket, position))
+        })
+    }
+
+    /// Returns the amount of blocks capable to store `len` bytes with the given alignment.
+    pub(crate) fn count(&self, len: usize, ptr_alignment: usize) -> usize {
+        self.buckets[self.bucket_for_request(len) ..]
+            .iter()
+            .flatten()
+            .filter(|entry| entry.growable.fits(len, ptr_alignment))
+            .count()
+    }
+}
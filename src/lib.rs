@@ -12,15 +12,46 @@
 //! [`GrowablePool`]: struct.GrowablePool.html
 
 #![deny(missing_docs, missing_debug_implementations)]
-#![feature(allocator_api, coerce_unsized, slice_ptr_get, unsize)]
+#![feature(allocator_api, coerce_unsized, ptr_metadata, slice_ptr_get, unsize)]
 
+mod arc;
+mod cache;
+mod free_list;
+mod local;
+mod pooled;
+mod rc;
+mod scope;
+mod shared;
+mod stats;
+mod string;
+mod sync;
+mod vec;
+
+pub use arc::{ReusableArc, WeakReusableArc};
+pub use cache::GrowableCache;
+pub use pooled::{LocalPooled, Pooled};
+pub use rc::{ReusableRc, WeakReusableRc};
+pub use scope::{GrowableScope, ScopedReusable};
+pub use shared::SyncGrowablePool;
+#[cfg(feature = "stats")]
+pub use stats::PoolStats;
+pub use string::ReusableString;
+pub use vec::ReusableVec;
+
+use free_list::{FreeList, Slot};
+use stats::{Counters, Regrowths};
 use std::{
-    alloc::{handle_alloc_error, Allocator, Global, Layout},
+    alloc::{handle_alloc_error, AllocError, Allocator, Global, Layout},
+    any::Any,
+    borrow::{Borrow, BorrowMut},
     cmp,
-    collections::VecDeque,
+    error::Error,
     fmt,
+    hash::{Hash, Hasher},
+    io,
+    iter::FusedIterator,
     marker::Unsize,
-    mem,
+    mem::{self, MaybeUninit},
     ops::{self, CoerceUnsized},
     ptr::{self, NonNull},
 };
@@ -40,14 +71,46 @@ use std::{
 /// ```
 ///
 /// [`GrowablePool`]: struct.GrowablePool.html
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GrowablePoolBuilder {
+#[derive(Debug, Clone)]
+pub struct GrowablePoolBuilder<A: Allocator = Global> {
     len: usize,
     per_growable_len: usize,
     per_growable_ptr_alignment: usize,
     overgrow: bool,
+    growth_policy: GrowthPolicy,
+    selection_policy: SelectionPolicy,
+    eviction_policy: EvictionPolicy,
+    refill_policy: RefillPolicy,
+    size_classes: Vec<usize>,
+    magazine_len: usize,
+    max_bytes: usize,
+    max_growable_len: usize,
+    alloc: A,
+}
+
+impl<A> PartialEq for GrowablePoolBuilder<A>
+where
+    A: Allocator,
+{
+    /// Compares the pool options only, the allocator is not taken into account.
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len &&
+            self.per_growable_len == other.per_growable_len &&
+            self.per_growable_ptr_alignment == other.per_growable_ptr_alignment &&
+            self.overgrow == other.overgrow &&
+            self.growth_policy == other.growth_policy &&
+            self.selection_policy == other.selection_policy &&
+            self.eviction_policy == other.eviction_policy &&
+            self.refill_policy == other.refill_policy &&
+            self.size_classes == other.size_classes &&
+            self.magazine_len == other.magazine_len &&
+            self.max_bytes == other.max_bytes &&
+            self.max_growable_len == other.max_growable_len
+    }
 }
 
+impl<A> Eq for GrowablePoolBuilder<A> where A: Allocator {}
+
 impl Default for GrowablePoolBuilder {
     fn default() -> Self {
         GrowablePoolBuilder::new()
@@ -57,11 +120,66 @@ impl Default for GrowablePoolBuilder {
 impl GrowablePoolBuilder {
     /// Creates a new pool builder with default options.
     pub fn new() -> Self {
+        GrowablePoolBuilder::new_in(Global)
+    }
+}
+
+impl<A> GrowablePoolBuilder<A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new pool builder with default options and the given allocator.
+    pub fn new_in(alloc: A) -> Self {
         GrowablePoolBuilder {
             len: 0,
             per_growable_len: 8,
             per_growable_ptr_alignment: 8,
             overgrow: true,
+            growth_policy: GrowthPolicy::Exact,
+            selection_policy: SelectionPolicy::Lifo,
+            eviction_policy: EvictionPolicy::DropIncoming,
+            refill_policy: RefillPolicy::Capacity,
+            size_classes: Vec::new(),
+            magazine_len: shared::DEFAULT_MAGAZINE_LEN,
+            max_bytes: usize::MAX,
+            max_growable_len: usize::MAX,
+            alloc,
+        }
+    }
+
+    /// Returns a copy of this builder that uses the given allocator for each [`Growable`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # use growable::*;
+    /// # use std::alloc::System;
+    ///   let _ = GrowablePool::builder()
+    ///       .with_capacity(16)
+    ///       .with_allocator(System)
+    ///       .build();
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn with_allocator<B>(&self, alloc: B) -> GrowablePoolBuilder<B>
+    where
+        B: Allocator + Clone,
+    {
+        GrowablePoolBuilder {
+            len: self.len,
+            per_growable_len: self.per_growable_len,
+            per_growable_ptr_alignment: self.per_growable_ptr_alignment,
+            overgrow: self.overgrow,
+            growth_policy: self.growth_policy,
+            selection_policy: self.selection_policy,
+            eviction_policy: self.eviction_policy,
+            refill_policy: self.refill_policy,
+            size_classes: self.size_classes.clone(),
+            magazine_len: self.magazine_len,
+            max_bytes: self.max_bytes,
+            max_growable_len: self.max_growable_len,
+            alloc,
         }
     }
 
@@ -90,6 +208,139 @@ impl GrowablePoolBuilder {
         self
     }
 
+    /// Sets the [`GrowthPolicy`] for each allocated [`Growable`].
+    ///
+    /// # Notes
+    ///
+    /// Every [`Growable`] returned to the pool adopts this policy as well.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`GrowthPolicy`]: enum.GrowthPolicy.html
+    pub fn with_growth_policy(&mut self, growth_policy: GrowthPolicy) -> &mut Self {
+        self.growth_policy = growth_policy;
+        self
+    }
+
+    /// Sets the [`SelectionPolicy`] used to pick a [`Growable`] for each allocation.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`SelectionPolicy`]: enum.SelectionPolicy.html
+    pub fn with_selection_policy(&mut self, selection_policy: SelectionPolicy) -> &mut Self {
+        self.selection_policy = selection_policy;
+        self
+    }
+
+    /// Sets the size classes in bytes used to segregate free [`Growable`] objects, each class
+    /// gets its own bucket plus the one for everything larger than the largest class.
+    /// An allocation goes straight to the bucket of the smallest class capable to store the value
+    /// and a returned [`Growable`] is filed by its capacity.
+    ///
+    /// # Notes
+    ///
+    /// The alignment is not a part of a class, a [`Growable`] with insufficient alignment
+    /// is regrown same as a too small one, according to the [`SelectionPolicy`]
+    /// used within a bucket.
+    ///
+    /// Classes are sorted and deduplicated, zero is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder()
+    ///       .with_size_classes(&[32, 64, 256, 4096])
+    ///       .with_capacity(4)
+    ///       .build();
+    ///   let small = Reusable::free(pool.allocate(0u8));
+    ///   let large = Reusable::free(pool.allocate([0u8; 1024]));
+    ///   assert!(small.len() < large.len());
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`SelectionPolicy`]: enum.SelectionPolicy.html
+    pub fn with_size_classes(&mut self, size_classes: &[usize]) -> &mut Self {
+        let mut size_classes = size_classes.to_vec();
+        size_classes.retain(|&size_class| size_class != 0);
+        size_classes.sort_unstable();
+        size_classes.dedup();
+        self.size_classes = size_classes;
+        self
+    }
+
+    /// Sets the amount of [`Growable`] objects each [`GrowableCache`] of a [`SyncGrowablePool`]
+    /// keeps per magazine, a cache holds up to two magazines at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`GrowableCache`]: struct.GrowableCache.html
+    /// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+    pub fn with_magazine_capacity(&mut self, len: usize) -> &mut Self {
+        assert_ne!(len, 0, "GrowablePoolBuilder::with_magazine_capacity: zero capacity");
+        self.magazine_len = len;
+        self
+    }
+
+    /// Sets the total amount of bytes the pool is allowed to hold, a returning [`Growable`]
+    /// that does not fit into this budget is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_max_bytes(1024).build();
+    ///   pool.free(Growable::with_capacity(768, 8));
+    ///   pool.free(Growable::with_capacity(768, 8));
+    ///   assert_eq!(pool.len(), 1);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn with_max_bytes(&mut self, max_bytes: usize) -> &mut Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the capacity limit for a [`Growable`] held by the pool, a larger one is shrunk
+    /// once returned (or dropped if the allocator fails to shrink it).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_max_growable_len(256).build();
+    ///   pool.free(Growable::with_capacity(4096, 8));
+    ///   let growable = Reusable::free(pool.allocate(0u8));
+    ///   assert_eq!(growable.len(), 256);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn with_max_growable_len(&mut self, len: usize) -> &mut Self {
+        self.max_growable_len = len;
+        self
+    }
+
+    /// Sets the [`EvictionPolicy`] used to pick a [`Growable`] to drop
+    /// once the pool is full or out of its memory budget.
+    ///
+    /// [`EvictionPolicy`]: enum.EvictionPolicy.html
+    /// [`Growable`]: struct.Growable.html
+    pub fn with_eviction_policy(&mut self, eviction_policy: EvictionPolicy) -> &mut Self {
+        self.eviction_policy = eviction_policy;
+        self
+    }
+
+    /// Sets the [`RefillPolicy`] used to decide how many [`Growable`] objects to allocate
+    /// once the pool has none to serve an allocation.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`RefillPolicy`]: enum.RefillPolicy.html
+    pub fn with_refill_policy(&mut self, refill_policy: RefillPolicy) -> &mut Self {
+        self.refill_policy = refill_policy;
+        self
+    }
+
     /// Sets a pool capacity used for every pool reallocation. Note that with `overgrow`
     /// enabled it is possible for the pool to grow beyond this capacity.
     /// If set to zero the pool will only allocate a [`Growable`] on an explicit allocation request.
@@ -103,22 +354,164 @@ impl GrowablePoolBuilder {
     /// Creates a new [`GrowablePool`] using this builder.
     ///
     /// [`GrowablePool`]: struct.GrowablePool.html
-    pub fn build(&self) -> GrowablePool {
-        let vec = {
-            let default =
-                Growable::with_capacity(self.per_growable_len, self.per_growable_ptr_alignment);
-            let mut vec = VecDeque::with_capacity(self.len);
-            vec.resize(self.len, default);
-            vec
-        };
+    pub fn build(&self) -> GrowablePool<A> {
+        let mut free = FreeList::new(&self.size_classes);
+        for _ in 0 .. self.len {
+            let growable = Growable::with_capacity_in(
+                self.per_growable_len,
+                self.per_growable_ptr_alignment,
+                self.alloc.clone(),
+            )
+            .with_growth_policy(self.growth_policy);
+            free.push(growable, self.selection_policy);
+        }
         GrowablePool {
             len: self.len,
             per_growable_len: self.per_growable_len,
             per_growable_ptr_alignment: self.per_growable_ptr_alignment,
             overgrow: self.overgrow,
-            vec,
+            growth_policy: self.growth_policy,
+            selection_policy: self.selection_policy,
+            eviction_policy: self.eviction_policy,
+            refill_policy: self.refill_policy,
+            geometric_batch: 1,
+            max_bytes: self.max_bytes,
+            max_growable_len: self.max_growable_len,
+            alloc: self.alloc.clone(),
+            free,
+            counters: Counters::default(),
         }
     }
+
+    /// Creates a new [`SyncGrowablePool`] using this builder.
+    ///
+    /// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+    pub fn build_shared(&self) -> SyncGrowablePool<A> {
+        SyncGrowablePool::with_magazine_capacity(self.build(), self.magazine_len)
+    }
+}
+
+/// A policy used by a [`GrowablePool`] to pick a [`Growable`] for each allocation.
+///
+/// [`Growable`]: struct.Growable.html
+/// [`GrowablePool`]: struct.GrowablePool.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SelectionPolicy {
+    /// Picks the most recently returned [`Growable`], regrowing it if needed.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[default]
+    Lifo,
+    /// Picks the least recently returned [`Growable`], regrowing it if needed.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    Fifo,
+    /// Picks the most recently returned [`Growable`] capable to store the value.
+    /// Falls back to [`SelectionPolicy::Lifo`] if there is none.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`SelectionPolicy::Lifo`]: enum.SelectionPolicy.html#variant.Lifo
+    FirstFit,
+    /// Picks the smallest [`Growable`] capable to store the value.
+    /// Falls back to the largest one if there is none, so it is regrown as little as possible.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    BestFit,
+}
+
+/// A policy used by a [`GrowablePool`] to decide which [`Growable`] to drop once the pool
+/// is full (with overgrow disabled) or out of its memory budget.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+///   let mut pool = GrowablePool::builder()
+///       .with_default_capacity(8)
+///       .with_capacity(2)
+///       .enable_overgrow(false)
+///       .with_eviction_policy(EvictionPolicy::DropSmallest)
+///       .build();
+///   pool.free(Growable::with_capacity(256, 8));
+///   assert_eq!(pool.len(), 2);
+///   assert_eq!(pool.bytes(), 8 + 256);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`GrowablePool`]: struct.GrowablePool.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// Drops the returning [`Growable`].
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[default]
+    DropIncoming,
+    /// Drops the smallest [`Growable`], which might be the returning one.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    DropSmallest,
+    /// Drops the largest [`Growable`], which might be the returning one.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    DropLargest,
+    /// Drops the least recently returned [`Growable`] held by the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    DropLeastRecentlyUsed,
+}
+
+/// A policy used by a [`GrowablePool`] to decide how many [`Growable`] objects to allocate
+/// once it has none to serve an allocation.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+///   let mut pool = GrowablePool::builder()
+///       .with_capacity(64)
+///       .with_refill_policy(RefillPolicy::Geometric(16))
+///       .build();
+///   pool.trim_to_len(0);
+///   let num = pool.allocate(0u8);
+///   pool.free(num);
+///   assert_eq!(pool.len(), 1);
+///   pool.trim_to_len(0);
+///   let num = pool.allocate(0u8);
+///   pool.free(num);
+///   assert_eq!(pool.len(), 2);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`GrowablePool`]: struct.GrowablePool.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RefillPolicy {
+    /// Allocates as much [`Growable`] objects as the pool capacity is, but at least one.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[default]
+    Capacity,
+    /// Allocates a single [`Growable`] at a time.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    Single,
+    /// Allocates the given amount of [`Growable`] objects, but at least one.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    Batch(usize),
+    /// Allocates a single [`Growable`] first and then twice as much on each next refill,
+    /// up to the given amount.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    Geometric(usize),
+    /// Never refills the pool on its own, leaving it to [`GrowablePool::reserve`]
+    /// (possibly called on a [`SyncGrowablePool`] by a background thread). A miss still has
+    /// to allocate a single [`Growable`] but [`GrowablePool::try_allocate`] fails instead.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`GrowablePool::reserve`]: struct.GrowablePool.html#method.reserve
+    /// [`GrowablePool::try_allocate`]: struct.GrowablePool.html#method.try_allocate
+    /// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+    Manual,
 }
 
 /// A pool of [`Growable`] objects. Unlike a typical Arena-based allocator it probably
@@ -182,28 +575,50 @@ impl GrowablePoolBuilder {
 ///
 /// [`Growable`]: struct.Growable.html
 /// [`GrowablePool`]: struct.GrowablePool.html
-pub struct GrowablePool {
+pub struct GrowablePool<A: Allocator = Global> {
     len: usize,
     per_growable_len: usize,
     per_growable_ptr_alignment: usize,
     overgrow: bool,
-    vec: VecDeque<Growable>,
+    growth_policy: GrowthPolicy,
+    selection_policy: SelectionPolicy,
+    eviction_policy: EvictionPolicy,
+    refill_policy: RefillPolicy,
+    geometric_batch: usize,
+    max_bytes: usize,
+    max_growable_len: usize,
+    alloc: A,
+    free: FreeList<A>,
+    counters: Counters,
 }
 
-impl Clone for GrowablePool {
+impl<A> Clone for GrowablePool<A>
+where
+    A: Allocator + Clone,
+{
     fn clone(&self) -> Self {
-        GrowablePoolBuilder::default()
+        GrowablePoolBuilder::new_in(self.alloc.clone())
             .with_default_capacity(self.per_growable_len)
             .with_default_ptr_alignment(self.per_growable_ptr_alignment)
             .with_capacity(self.len)
             .enable_overgrow(self.overgrow)
+            .with_growth_policy(self.growth_policy)
+            .with_selection_policy(self.selection_policy)
+            .with_eviction_policy(self.eviction_policy)
+            .with_refill_policy(self.refill_policy)
+            .with_size_classes(self.free.size_classes())
+            .with_max_bytes(self.max_bytes)
+            .with_max_growable_len(self.max_growable_len)
             .build()
     }
 }
 
-impl fmt::Debug for GrowablePool {
+impl<A> fmt::Debug for GrowablePool<A>
+where
+    A: Allocator,
+{
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "GrowablePool {{ .. {} more allocations available .. }}", self.vec.len())
+        write!(formatter, "GrowablePool {{ .. {} more allocations available .. }}", self.free.len())
     }
 }
 
@@ -229,6 +644,28 @@ impl GrowablePool {
     pub fn builder() -> GrowablePoolBuilder {
         GrowablePoolBuilder::default()
     }
+}
+
+impl<A> GrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new pool with default options and the given allocator.
+    ///
+    /// # Notes
+    ///
+    /// See [`GrowablePoolBuilder`] for advanced configuration.
+    ///
+    /// [`GrowablePoolBuilder`]: struct.GrowablePoolBuilder.html
+    pub fn new_in(alloc: A) -> Self {
+        GrowablePoolBuilder::new_in(alloc).build()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
 
     /// Returns true if a reallocation will be needed to allocate an another one object.
     #[inline]
@@ -239,7 +676,49 @@ impl GrowablePool {
     /// Returns the current amount of allocations that this pool can provide without a reallocation.
     #[inline]
     pub fn len(&self) -> usize {
-        self.vec.len()
+        self.free.len()
+    }
+
+    /// Returns the total capacity in bytes of all [`Growable`] objects held by the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.free.bytes()
+    }
+
+    /// Returns a snapshot of the pool statistics.
+    ///
+    /// # Notes
+    ///
+    /// Only available with the `stats` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_capacity(1).build();
+    ///   let num = pool.allocate(0u64);
+    ///   pool.free(num);
+    ///   let stats = pool.stats();
+    ///   assert_eq!(stats.allocations, 1);
+    ///   assert_eq!(stats.frees, 1);
+    ///   assert_eq!(stats.bytes_held, 8);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> PoolStats {
+        self.counters.get(self.free.bytes())
+    }
+
+    /// Resets the pool statistics. The amount of bytes currently taken from the pool
+    /// is kept and becomes the new peak.
+    ///
+    /// # Notes
+    ///
+    /// Only available with the `stats` feature enabled.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.counters.reset();
     }
 
     /// Allocates a new [`Reusable`] from the pool.
@@ -251,54 +730,706 @@ impl GrowablePool {
     /// [`Growable`]: struct.Growable.html
     /// [`Reusable`]: struct.Reusable.html
     #[inline]
-    pub fn allocate<T>(&mut self, t: T) -> Reusable<T> {
-        match self.vec.pop_front() {
-            Some(growable) => growable.consume(t),
-            None => {
-                let default =
-                    Growable::with_capacity(self.per_growable_len, self.per_growable_ptr_alignment);
-                self.vec.resize(cmp::max(self.len, 1), default);
-                self.allocate(t)
-            },
-        }
+    pub fn allocate<T>(&mut self, t: T) -> Reusable<T, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume(t)
     }
 
-    /// Returns the [`Reusable`] back to the pool, marking it
-    /// available for a next allocation.
+    /// Allocates a new [`Reusable`] from the pool, storing the value returned
+    /// by the given closure.
     ///
     /// # Notes
     ///
-    /// With overgrow disabled the [`Growable`] might be dropped entirely if
-    /// there is not enough free space available in the pool.
+    /// If the closure panics the [`Growable`] is dropped along with its memory. The value is
+    /// not constructed in place, see [`Growable::consume_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   let arr = pool.allocate_with(|| [0u8; 1024]);
+    ///   assert_eq!(arr.len(), 1024);
+    /// ```
     ///
     /// [`Growable`]: struct.Growable.html
+    /// [`Growable::consume_with`]: struct.Growable.html#method.consume_with
     /// [`Reusable`]: struct.Reusable.html
     #[inline]
-    pub fn free<T>(&mut self, t: Reusable<T>)
+    pub fn allocate_with<T, F>(&mut self, f: F) -> Reusable<T, A>
     where
-        T: ?Sized,
+        F: FnOnce() -> T,
     {
-        if !self.overgrow && self.vec.len() >= self.len {
-            return;
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_with(f)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool with an uninitialized value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   let mut num = pool.allocate_uninit::<u64>();
+    ///   num.write(42);
+    ///   let num = unsafe { Reusable::assume_init(num) };
+    ///   assert_eq!(*num, 42);
+    /// ```
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_uninit<T>(&mut self) -> Reusable<MaybeUninit<T>, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_uninit()
+    }
+
+    /// Tries to allocate a new [`Reusable`] from the pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if the memory allocation failed, in this case the [`Growable`]
+    /// that was selected for allocation is returned back to the pool.
+    ///
+    /// # Notes
+    ///
+    /// If no [`Growable`] is available for allocation, the pool will be reallocated with as much
+    /// objects as the allocator can provide, up to the [`RefillPolicy`] batch. With
+    /// [`RefillPolicy::Manual`] the allocation fails instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   let num = pool.try_allocate(0usize).unwrap();
+    ///   assert_eq!(*num, 0usize);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`RefillPolicy`]: enum.RefillPolicy.html
+    /// [`RefillPolicy::Manual`]: enum.RefillPolicy.html#variant.Manual
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn try_allocate<T>(&mut self, t: T) -> Result<Reusable<T, A>, T> {
+        match self.try_take(mem::size_of::<T>(), mem::align_of::<T>()) {
+            Ok((growable, slot)) => {
+                growable.try_consume(t).map_err(|(t, growable)| {
+                    self.put_back(slot, growable);
+                    t
+                })
+            },
+            Err(AllocError) => Err(t),
         }
-        self.vec.push_front(Reusable::free(t));
     }
-}
 
-/// A chunk of the heap memory that can be assigned with an arbitrary type.
-///
-/// # Examples
-///
-/// First, let's spawn a new [`Growable`]. In this case no allocation will be performed.
-///
-/// ```
-/// # use growable::*;
-///   let growable = Growable::new();
-/// # let arr: Reusable<[char; 3]> = growable.consume(['f', 'o', 'o']);
-/// # assert_eq!(&*arr, &['f', 'o', 'o']);
-/// # let growable = Reusable::free(arr);
-/// # let arr: Reusable<[char; 6]> = growable.consume(['f', 'o', 'o', 'b', 'a', 'r']);
-/// # assert_eq!(&*arr, &['f', 'o', 'o', 'b', 'a', 'r']);
+    /// Allocates a new [`Reusable`] from the pool but only if there is a [`Growable`] already
+    /// capable to store the value, never touching the allocator.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if no suitable [`Growable`] is available in the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder()
+    ///       .with_default_capacity(8)
+    ///       .with_default_ptr_alignment(8)
+    ///       .with_capacity(1)
+    ///       .build();
+    ///   assert_eq!(pool.allocate_no_grow([0u64; 2]).unwrap_err(), [0u64; 2]);
+    ///   let num = pool.allocate_no_grow(0u64).unwrap();
+    ///   assert_eq!(*num, 0u64);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_no_grow<T>(&mut self, t: T) -> Result<Reusable<T, A>, T> {
+        let (len, ptr_alignment) = (mem::size_of::<T>(), mem::align_of::<T>());
+        match self.free.find(len, ptr_alignment) {
+            Some(position) => {
+                let (growable, stamp) =
+                    self.free.remove_stamped(position).expect("GrowablePool::allocate_no_grow");
+                self.counters.allocation();
+                self.counters.hit();
+                self.counters.take(&growable, len, ptr_alignment);
+                // NB: The list only finds blocks that fit, the put-back is merely a safety net.
+                growable.try_reuse(t).map_err(|(t, growable)| {
+                    self.put_back((position, stamp), growable);
+                    t
+                })
+            },
+            None => Err(t),
+        }
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, moving an unsized value out of the [`Box`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::fmt::Debug;
+    ///   let mut pool = GrowablePool::default();
+    ///   let b: Box<dyn Debug> = Box::new(42);
+    ///   let v: Reusable<dyn Debug> = pool.allocate_box(b);
+    ///   assert_eq!(format!("{:?}", v), "42");
+    /// ```
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_box<T, B>(&mut self, b: Box<T, B>) -> Reusable<T, A>
+    where
+        T: ?Sized,
+        B: Allocator,
+    {
+        let layout = Layout::for_value::<T>(&b);
+        self.take(layout.size(), layout.align()).consume_box(b)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, cloning the slice into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_clone<T>(&mut self, slice: &[T]) -> Reusable<[T], A>
+    where
+        T: Clone,
+    {
+        let layout = Layout::for_value(slice);
+        self.take(layout.size(), layout.align()).consume_slice_clone(slice)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, collecting the iterator into a slice.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_from_iter<T, I>(&mut self, iter: I) -> Reusable<[T], A>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len().saturating_mul(mem::size_of::<T>());
+        self.take(len, mem::align_of::<T>()).consume_slice_from_iter(iter)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, copying the string into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_str(&mut self, s: &str) -> Reusable<str, A> {
+        self.take(s.len(), 1).consume_str(s)
+    }
+
+    /// Allocates a new empty [`ReusableVec`] from the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   let mut vec = pool.allocate_vec();
+    ///   vec.push(1u8);
+    ///   pool.free(vec);
+    /// ```
+    ///
+    /// [`ReusableVec`]: struct.ReusableVec.html
+    #[inline]
+    pub fn allocate_vec<T>(&mut self) -> ReusableVec<T, A> {
+        ReusableVec::from_growable(self.take(0, 1))
+    }
+
+    /// Allocates a new empty [`ReusableString`] from the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   let mut s = pool.allocate_string();
+    ///   s.push_str("Hello World");
+    ///   pool.free(s);
+    /// ```
+    ///
+    /// [`ReusableString`]: struct.ReusableString.html
+    #[inline]
+    pub fn allocate_string(&mut self) -> ReusableString<A> {
+        ReusableString::from_growable(self.take(0, 1))
+    }
+
+    /// Takes a [`Growable`] from the pool to store `len` bytes with the given alignment,
+    /// reallocating the matching bucket if it is empty.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn take(&mut self, len: usize, ptr_alignment: usize) -> Growable<A> {
+        self.counters.allocation();
+        if self.prepare(len, ptr_alignment) {
+            self.counters.hit();
+        }
+        let growable = self
+            .select(len, ptr_alignment)
+            .and_then(|position| self.free.remove(position))
+            .expect("GrowablePool::take: the pool is empty");
+        self.counters.take(&growable, len, ptr_alignment);
+        growable
+    }
+
+    /// Takes a [`Growable`] from the pool to store `len` bytes with the given alignment along
+    /// with up to `count` more of them into the batch, each one is counted as an allocation.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub(crate) fn take_batch(
+        &mut self,
+        len: usize,
+        ptr_alignment: usize,
+        batch: &mut Vec<Growable<A>>,
+        count: usize,
+    ) -> Growable<A> {
+        let growable = self.take(len, ptr_alignment);
+        let start = batch.len();
+        self.free.pop_batch(count, batch);
+        for growable in &batch[start ..] {
+            self.counters.allocation();
+            self.counters.hit();
+            self.counters.take(growable, growable.len, growable.ptr_alignment);
+        }
+        growable
+    }
+
+    /// Makes sure there is a [`Growable`] to store `len` bytes with the given alignment,
+    /// reallocating the matching bucket if it is empty. Returns whether one was already there.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn prepare(&mut self, len: usize, ptr_alignment: usize) -> bool {
+        if self.select(len, ptr_alignment).is_none() {
+            self.counters.refill();
+            let bucket = self.free.bucket_for_request(len);
+            let default = Growable::with_capacity_in(
+                self.refill_len(bucket, len),
+                self.per_growable_ptr_alignment,
+                self.alloc.clone(),
+            )
+            .with_growth_policy(self.growth_policy);
+            for _ in 1 .. self.refill_batch() {
+                self.free.push_into(bucket, default.clone(), SelectionPolicy::Fifo);
+            }
+            self.free.push_into(bucket, default, SelectionPolicy::Fifo);
+            return false;
+        }
+        true
+    }
+
+    /// Takes a [`Growable`] from the pool to store `len` bytes with the given alignment,
+    /// reallocating the matching bucket if it is empty. Returns it along with the slot it was
+    /// taken from, so it can be put back there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bucket is empty and the allocator failed to refill it,
+    /// or if it is not refilled at all because of [`RefillPolicy::Manual`].
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`RefillPolicy::Manual`]: enum.RefillPolicy.html#variant.Manual
+    fn try_take(
+        &mut self,
+        len: usize,
+        ptr_alignment: usize,
+    ) -> Result<(Growable<A>, Slot), AllocError> {
+        self.counters.allocation();
+        if self.select(len, ptr_alignment).is_some() {
+            self.counters.hit();
+        } else if self.refill_policy != RefillPolicy::Manual {
+            self.counters.refill();
+            self.try_refill(len);
+        }
+        let position = self.select(len, ptr_alignment).ok_or(AllocError)?;
+        let (growable, stamp) = self.free.remove_stamped(position).expect("GrowablePool::try_take");
+        self.counters.take(&growable, len, ptr_alignment);
+        Ok((growable, (position, stamp)))
+    }
+
+    /// Puts a [`Growable`] back to the slot it was taken from after a failed allocation,
+    /// keeping its age.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn put_back(&mut self, (position, stamp): Slot, growable: Growable<A>) {
+        self.counters.put_back(growable.len);
+        self.free.insert(position, growable, stamp);
+    }
+
+    /// Reallocates the bucket matching `len` with as much objects as the allocator can provide,
+    /// up to the [`RefillPolicy`] batch.
+    ///
+    /// [`RefillPolicy`]: enum.RefillPolicy.html
+    fn try_refill(&mut self, len: usize) {
+        let bucket = self.free.bucket_for_request(len);
+        for _ in 0 .. self.refill_batch() {
+            match Growable::try_with_capacity_in(
+                self.refill_len(bucket, len),
+                self.per_growable_ptr_alignment,
+                self.alloc.clone(),
+            ) {
+                Ok(growable) => {
+                    self.free.push_into(
+                        bucket,
+                        growable.with_growth_policy(self.growth_policy),
+                        SelectionPolicy::Fifo,
+                    )
+                },
+                Err(AllocError) => break,
+            }
+        }
+    }
+
+    /// Returns the position of a [`Growable`] to store `len` bytes with the given alignment
+    /// according to the selection policy.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    fn select(&self, len: usize, ptr_alignment: usize) -> Option<(usize, usize)> {
+        self.free.select(len, ptr_alignment, self.selection_policy)
+    }
+
+    /// Allocates [`Growable`] objects until there are at least `count` of them capable to store
+    /// `len` bytes with the given alignment.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn reserve_for_layout(&mut self, count: usize, len: usize, ptr_alignment: usize) {
+        let missing = count.saturating_sub(self.free.count(len, ptr_alignment));
+        if missing == 0 {
+            return;
+        }
+        let bucket = self.free.bucket_for_request(len);
+        let default = Growable::with_capacity_in(
+            cmp::max(self.refill_len(bucket, len), len),
+            cmp::max(self.per_growable_ptr_alignment, ptr_alignment),
+            self.alloc.clone(),
+        )
+        .with_growth_policy(self.growth_policy);
+        for _ in 1 .. missing {
+            self.free.push(default.clone(), self.selection_policy);
+        }
+        self.free.push(default, self.selection_policy);
+    }
+
+    /// Returns the amount of [`Growable`] objects to allocate for the next refill.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn refill_batch(&mut self) -> usize {
+        match self.refill_policy {
+            RefillPolicy::Capacity => cmp::max(self.len, 1),
+            RefillPolicy::Single | RefillPolicy::Manual => 1,
+            RefillPolicy::Batch(len) => cmp::max(len, 1),
+            RefillPolicy::Geometric(len) => {
+                let batch = cmp::min(self.geometric_batch, cmp::max(len, 1));
+                self.geometric_batch = batch.saturating_mul(2);
+                batch
+            },
+        }
+    }
+
+    /// Returns the capacity of each [`Growable`] allocated to refill the bucket
+    /// serving requests of `len` bytes.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    fn refill_len(&self, bucket: usize, len: usize) -> usize {
+        match (self.free.size_class(bucket), self.free.size_classes().last()) {
+            (Some(size_class), _) => cmp::max(self.per_growable_len, size_class),
+            // NB: Anything smaller would not belong to the bucket for everything
+            // larger than the largest class.
+            (None, Some(&largest)) => cmp::max(self.per_growable_len, cmp::max(len, largest)),
+            (None, None) => self.per_growable_len,
+        }
+    }
+
+    /// Returns the [`Reusable`] back to the pool, marking it
+    /// available for a next allocation.
+    ///
+    /// # Notes
+    ///
+    /// Anything convertible into a [`Growable`] can be returned, for example a [`Growable`] itself
+    /// or a [`Vec`] which memory is then reused by the pool.
+    ///
+    /// With overgrow disabled the [`Growable`] (or an another one held by the pool, according
+    /// to the [`EvictionPolicy`]) might be dropped entirely if there is not enough free space
+    /// available in the pool, the same goes for the pool memory budget.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::default();
+    ///   pool.free(vec![0u8; 64]);
+    ///   assert_eq!(pool.len(), 1);
+    /// ```
+    ///
+    /// [`EvictionPolicy`]: enum.EvictionPolicy.html
+    /// [`Growable`]: struct.Growable.html
+    /// [`Reusable`]: struct.Reusable.html
+    /// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+    #[inline]
+    pub fn free<G>(&mut self, t: G)
+    where
+        G: Into<Growable<A>>,
+    {
+        let mut growable = t.into();
+        self.counters.regrow(mem::take(&mut growable.regrowths));
+        let len = growable.len;
+        let kept = self.file(growable);
+        self.counters.free(len, !kept);
+    }
+
+    /// Returns a batch of [`Growable`] objects to the pool, each one is counted as a free.
+    /// The pool capacity and memory budget are only checked once for the objects fitting
+    /// into them, the rest are returned one by one according to the eviction policy.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub(crate) fn free_batch<I>(&mut self, batch: I)
+    where
+        I: IntoIterator<Item = Growable<A>>,
+    {
+        let mut room_len = match self.overgrow {
+            true => usize::MAX,
+            false => self.len.saturating_sub(self.free.len()),
+        };
+        let mut room_bytes = self.max_bytes.saturating_sub(self.free.bytes());
+        let mut rest = Vec::new();
+        let fitting = batch.into_iter().filter_map(|mut growable| {
+            self.counters.regrow(mem::take(&mut growable.regrowths));
+            let len = growable.len;
+            if !growable.shrink(self.max_growable_len) {
+                self.counters.free(len, true);
+                return None;
+            }
+            if room_len == 0 || growable.len > room_bytes {
+                rest.push(growable);
+                return None;
+            }
+            room_len -= 1;
+            room_bytes -= growable.len;
+            growable.set_growth_policy(self.growth_policy);
+            self.counters.free(len, false);
+            Some(growable)
+        });
+        self.free.push_batch(fitting, self.selection_policy);
+        for growable in rest {
+            let len = growable.len;
+            let kept = self.file(growable);
+            self.counters.free(len, !kept);
+        }
+    }
+
+    /// Files the [`Growable`] into the pool dropping other ones according to the eviction
+    /// policy if needed. Returns whether it was kept.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    fn file(&mut self, mut growable: Growable<A>) -> bool {
+        if !growable.shrink(self.max_growable_len) || growable.len > self.max_bytes {
+            return false;
+        }
+        let overflows = |count: usize, bytes: usize| {
+            (!self.overgrow && count >= self.len) ||
+                growable.len > self.max_bytes.saturating_sub(bytes)
+        };
+        let (mut count, mut bytes) = (self.free.len(), self.free.bytes());
+        if overflows(count, bytes) {
+            // NB: Victims are only dropped if this makes enough room for the returning one.
+            let mut victims = self.free.victims(growable.len, self.eviction_policy);
+            let mut stamps = Vec::new();
+            while overflows(count, bytes) {
+                match victims.next() {
+                    Some((stamp, victim_len)) => {
+                        count -= 1;
+                        bytes -= victim_len;
+                        stamps.push(stamp);
+                    },
+                    None => return false,
+                }
+            }
+            drop(victims);
+            self.counters.evict(stamps.len());
+            self.free.remove_all(stamps);
+        }
+        growable.set_growth_policy(self.growth_policy);
+        self.free.push(growable, self.selection_policy);
+        true
+    }
+
+    /// Drops the least recently returned [`Growable`] objects until
+    /// there are at most `len` of them left in the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_capacity(16).build();
+    ///   pool.trim_to_len(4);
+    ///   assert_eq!(pool.len(), 4);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn trim_to_len(&mut self, len: usize) {
+        self.free.trim(len, usize::MAX);
+    }
+
+    /// Drops the least recently returned [`Growable`] objects until
+    /// the pool holds at most `bytes` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder()
+    ///       .with_default_capacity(64)
+    ///       .with_capacity(16)
+    ///       .build();
+    ///   pool.trim_to_bytes(256);
+    ///   assert_eq!(pool.len(), 4);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn trim_to_bytes(&mut self, bytes: usize) {
+        self.free.trim(usize::MAX, bytes);
+    }
+
+    /// Drops the least recently returned [`Growable`] objects that overgrew the pool
+    /// capacity and releases any spare memory the pool itself holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_capacity(2).build();
+    ///   for _ in 0 .. 8 {
+    ///       pool.free(Growable::new());
+    ///   }
+    ///   pool.shrink_to_fit();
+    ///   assert_eq!(pool.len(), 2);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn shrink_to_fit(&mut self) {
+        self.trim_to_len(self.len);
+        self.free.shrink_to_fit();
+    }
+
+    /// Allocates default [`Growable`] objects until there are at least `len` of them
+    /// available in the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder()
+    ///       .with_refill_policy(RefillPolicy::Manual)
+    ///       .build();
+    ///   pool.reserve(4);
+    ///   assert_eq!(pool.len(), 4);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn reserve(&mut self, len: usize) {
+        self.reserve_for_layout(len, 0, 1);
+    }
+
+    /// Allocates [`Growable`] objects until there are at least `len` of them
+    /// capable to store a value of type `T` available in the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder()
+    ///       .with_refill_policy(RefillPolicy::Manual)
+    ///       .build();
+    ///   pool.reserve_for::<[u64; 32]>(4);
+    ///   assert_eq!(pool.allocate_no_grow([0u64; 32]).unwrap().len(), 32);
+    ///   assert_eq!(pool.len(), 3);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn reserve_for<T>(&mut self, len: usize) {
+        self.reserve_for_layout(len, mem::size_of::<T>(), mem::align_of::<T>());
+    }
+}
+
+/// A policy that decides how much memory a [`Growable`] actually allocates whenever it has to grow.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+///   assert_eq!(GrowthPolicy::Exact.round(100), 100);
+///   assert_eq!(GrowthPolicy::PowerOfTwo.round(100), 128);
+///   assert_eq!(GrowthPolicy::SizeClasses.round(100), 112);
+///   assert_eq!(GrowthPolicy::Step(64).round(100), 128);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// Grows to exactly the requested size.
+    #[default]
+    Exact,
+    /// Rounds the requested size up to the nearest power of two.
+    PowerOfTwo,
+    /// Rounds the requested size up to the nearest jemalloc-like size class: multiples of 16 up to
+    /// 128 bytes and then four evenly spaced classes for every next power of two.
+    SizeClasses,
+    /// Rounds the requested size up to the nearest multiple of the given step.
+    /// A zero step is the same as [`GrowthPolicy::Exact`].
+    ///
+    /// [`GrowthPolicy::Exact`]: enum.GrowthPolicy.html#variant.Exact
+    Step(usize),
+}
+
+impl GrowthPolicy {
+    /// Returns the amount of memory to allocate for the requested `len`.
+    ///
+    /// # Notes
+    ///
+    /// Zero length is never rounded up, and neither is a length that would overflow.
+    pub fn round(self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        let rounded = match self {
+            GrowthPolicy::Exact => Some(len),
+            GrowthPolicy::PowerOfTwo => len.checked_next_power_of_two(),
+            GrowthPolicy::SizeClasses => {
+                let step = match len {
+                    0 ..= 8 => 8,
+                    9 ..= 128 => 16,
+                    _ => 1 << ((usize::BITS - 1 - (len - 1).leading_zeros()) - 2),
+                };
+                len.checked_next_multiple_of(step)
+            },
+            GrowthPolicy::Step(0) => Some(len),
+            GrowthPolicy::Step(step) => len.checked_next_multiple_of(step),
+        };
+        match rounded {
+            Some(rounded) if rounded <= isize::MAX as usize => rounded,
+            _ => len,
+        }
+    }
+}
+
+/// A chunk of the heap memory that can be assigned with an arbitrary type.
+///
+/// # Examples
+///
+/// First, let's spawn a new [`Growable`]. In this case no allocation will be performed.
+///
+/// ```
+/// # use growable::*;
+///   let growable = Growable::new();
+/// # let arr: Reusable<[char; 3]> = growable.consume(['f', 'o', 'o']);
+/// # assert_eq!(&*arr, &['f', 'o', 'o']);
+/// # let growable = Reusable::free(arr);
+/// # let arr: Reusable<[char; 6]> = growable.consume(['f', 'o', 'o', 'b', 'a', 'r']);
+/// # assert_eq!(&*arr, &['f', 'o', 'o', 'b', 'a', 'r']);
 /// ```
 ///
 /// Now we can assign some data to it.
@@ -327,30 +1458,43 @@ impl GrowablePool {
 /// ```
 ///
 /// [`Growable`]: struct.Growable.html
-pub struct Growable {
+pub struct Growable<A: Allocator = Global> {
     len: usize,
     ptr_alignment: usize,
     ptr: NonNull<u8>,
+    growth_policy: GrowthPolicy,
+    regrowths: Regrowths,
+    alloc: A,
 }
 
-unsafe impl Send for Growable {}
+unsafe impl<A> Send for Growable<A> where A: Allocator + Send {}
 
-unsafe impl Sync for Growable {}
+unsafe impl<A> Sync for Growable<A> where A: Allocator + Sync {}
 
-impl Clone for Growable {
+impl<A> Clone for Growable<A>
+where
+    A: Allocator + Clone,
+{
     #[inline]
     fn clone(&self) -> Self {
-        Self::with_capacity(self.len, self.ptr_alignment)
+        Self::with_capacity_in(self.len, self.ptr_alignment, self.alloc.clone())
+            .with_growth_policy(self.growth_policy)
     }
 }
 
-impl fmt::Pointer for Growable {
+impl<A> fmt::Pointer for Growable<A>
+where
+    A: Allocator,
+{
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.ptr, formatter)
     }
 }
 
-impl fmt::Debug for Growable {
+impl<A> fmt::Debug for Growable<A>
+where
+    A: Allocator,
+{
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self.len {
             0 => write!(formatter, "Growable::None"),
@@ -365,18 +1509,24 @@ impl fmt::Debug for Growable {
     }
 }
 
-impl Default for Growable {
+impl<A> Default for Growable<A>
+where
+    A: Allocator + Default,
+{
     #[inline]
     fn default() -> Self {
-        Self::new()
+        Self::new_in(A::default())
     }
 }
 
-impl Drop for Growable {
+impl<A> Drop for Growable<A>
+where
+    A: Allocator,
+{
     fn drop(&mut self) {
         if self.len != 0 {
             unsafe {
-                Global.deallocate(
+                self.alloc.deallocate(
                     self.ptr,
                     Layout::from_size_align_unchecked(self.len, self.ptr_alignment),
                 );
@@ -385,255 +1535,1258 @@ impl Drop for Growable {
     }
 }
 
-impl Growable {
-    /// Returns a new instance of `Growable` but does not allocate any memory on the heap yet.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use growable::*;
-    ///   let _ = Growable::new();
-    /// ```
-    ///
-    /// [`Growable`]: struct.Growable.html
-    #[inline]
-    pub fn new() -> Self {
-        Self::with_capacity(0, 1)
+impl Growable {
+    /// Returns a new instance of `Growable` but does not allocate any memory on the heap yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let _ = Growable::new();
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated on the heap suitable to
+    /// store an instance of a given type T.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   struct Foo {
+    ///       a: u8,
+    ///       b: usize,
+    ///       c: (),
+    ///   }
+    ///   let _ = Growable::with_capacity_for_type::<Foo>();
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn with_capacity_for_type<T>() -> Self {
+        Self::with_capacity_for_type_in::<T>(Global)
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated on the heap.
+    ///
+    /// # Panics
+    ///
+    /// * `ptr_alignment` is not a power of two.
+    /// * `len` overflows after being rounded up to the nearest multiple of the alignment.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let _ = Growable::with_capacity(256, 16);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn with_capacity(len: usize, ptr_alignment: usize) -> Self {
+        Self::with_capacity_in(len, ptr_alignment, Global)
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated on the heap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocator failed to allocate the memory.
+    ///
+    /// # Panics
+    ///
+    /// * `ptr_alignment` is not a power of two.
+    /// * `len` overflows after being rounded up to the nearest multiple of the alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let _ = Growable::try_with_capacity(256, 16).unwrap();
+    /// ```
+    #[inline]
+    pub fn try_with_capacity(len: usize, ptr_alignment: usize) -> Result<Self, AllocError> {
+        Self::try_with_capacity_in(len, ptr_alignment, Global)
+    }
+}
+
+impl<A> Growable<A>
+where
+    A: Allocator,
+{
+    /// Returns a new instance of `Growable` in the provided allocator
+    /// but does not allocate any memory yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # use growable::*;
+    /// # use std::alloc::System;
+    ///   let _ = Growable::new_in(System);
+    /// ```
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, 1, alloc)
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated in the provided
+    /// allocator suitable to store an instance of a given type T.
+    #[inline]
+    pub fn with_capacity_for_type_in<T>(alloc: A) -> Self {
+        Self::with_capacity_in(mem::size_of::<T>(), mem::align_of::<T>(), alloc)
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated in the provided
+    /// allocator.
+    ///
+    /// # Panics
+    ///
+    /// * `ptr_alignment` is not a power of two.
+    /// * `len` overflows after being rounded up to the nearest multiple of the alignment.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # use growable::*;
+    /// # use std::alloc::System;
+    ///   let _ = Growable::with_capacity_in(256, 16, System);
+    /// ```
+    #[inline]
+    pub fn with_capacity_in(len: usize, ptr_alignment: usize, alloc: A) -> Self {
+        let ptr = Self::allocate(&alloc, len, ptr_alignment)
+            .unwrap_or_else(|layout| handle_alloc_error(layout));
+        Growable {
+            len,
+            ptr_alignment,
+            ptr,
+            growth_policy: GrowthPolicy::Exact,
+            regrowths: Regrowths::default(),
+            alloc,
+        }
+    }
+
+    /// Returns a new instance of `Growable` with memory already allocated in the provided
+    /// allocator, or an error instead of calling the `alloc_error` handler on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocator failed to allocate the memory.
+    ///
+    /// # Panics
+    ///
+    /// * `ptr_alignment` is not a power of two.
+    /// * `len` overflows after being rounded up to the nearest multiple of the alignment.
+    #[inline]
+    pub fn try_with_capacity_in(
+        len: usize,
+        ptr_alignment: usize,
+        alloc: A,
+    ) -> Result<Self, AllocError> {
+        let ptr = Self::allocate(&alloc, len, ptr_alignment).map_err(|_| AllocError)?;
+        Ok(Growable {
+            len,
+            ptr_alignment,
+            ptr,
+            growth_policy: GrowthPolicy::Exact,
+            regrowths: Regrowths::default(),
+            alloc,
+        })
+    }
+
+    /// Returns true if no memory has been allocated yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the amount of memory allocated by this `Growable`.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the alignment.
+    #[inline]
+    pub fn alignment(&self) -> usize {
+        self.ptr_alignment
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the [`GrowthPolicy`] used by this `Growable`.
+    ///
+    /// [`GrowthPolicy`]: enum.GrowthPolicy.html
+    #[inline]
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth_policy
+    }
+
+    /// Sets the [`GrowthPolicy`] used by this `Growable`.
+    ///
+    /// [`GrowthPolicy`]: enum.GrowthPolicy.html
+    #[inline]
+    pub fn set_growth_policy(&mut self, growth_policy: GrowthPolicy) {
+        self.growth_policy = growth_policy;
+    }
+
+    /// Returns this `Growable` with the given [`GrowthPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::new().with_growth_policy(GrowthPolicy::PowerOfTwo);
+    ///   let arr = growable.consume([0u8; 100]);
+    ///   let growable = Reusable::free(arr);
+    ///   assert_eq!(growable.len(), 128);
+    /// ```
+    ///
+    /// [`GrowthPolicy`]: enum.GrowthPolicy.html
+    #[inline]
+    pub fn with_growth_policy(mut self, growth_policy: GrowthPolicy) -> Self {
+        self.growth_policy = growth_policy;
+        self
+    }
+
+    /// Places an instance of `T` on the heap, an actual (re)allocation will be performed
+    /// only if there is not enough space or the pointer alignment is invalid.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::with_capacity(128, 8);
+    ///   let num = growable.consume(0usize);
+    ///   assert_eq!(*num, 0usize);
+    /// ```
+    #[inline]
+    pub fn consume<T>(mut self, t: T) -> Reusable<T, A> {
+        if let Err(layout) = self.grow(mem::size_of::<T>(), mem::align_of::<T>(), false) {
+            handle_alloc_error(layout);
+        }
+        self.copy(t)
+    }
+
+    /// Tries to place an instance of `T` on the heap, an actual (re)allocation will be performed
+    /// only if there is not enough space or the pointer alignment is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back along with this `Growable` left intact
+    /// if the memory allocation failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::new();
+    ///   let num = growable.try_consume(0usize).unwrap();
+    ///   assert_eq!(*num, 0usize);
+    /// ```
+    #[inline]
+    pub fn try_consume<T>(mut self, t: T) -> Result<Reusable<T, A>, (T, Self)> {
+        match self.grow(mem::size_of::<T>(), mem::align_of::<T>(), true) {
+            Ok(()) => Ok(self.copy(t)),
+            Err(_) => Err((t, self)),
+        }
+    }
+
+    /// Places an instance of `T` returned by the given closure on the heap, an actual
+    /// (re)allocation will be performed only if there is not enough space or the pointer alignment
+    /// is invalid.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler. If the closure panics this `Growable` is dropped
+    /// along with its memory.
+    ///
+    /// The memory is prepared before the closure is called, but the value is still returned
+    /// by it and then moved into the memory, so it is not constructed in place (unless the
+    /// compiler elides the copy, which is not guaranteed). Use [`consume_uninit`] to initialize
+    /// a large value through a pointer instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::new();
+    ///   let arr = growable.consume_with(|| [0u8; 1024]);
+    ///   assert_eq!(arr.len(), 1024);
+    /// ```
+    ///
+    /// [`consume_uninit`]: struct.Growable.html#method.consume_uninit
+    #[inline]
+    pub fn consume_with<T, F>(self, f: F) -> Reusable<T, A>
+    where
+        F: FnOnce() -> T,
+    {
+        let uninit = self.consume_uninit::<T>();
+        Reusable::write(uninit, f())
+    }
+
+    /// Prepares the memory to store an instance of `T` but leaves it uninitialized, an actual
+    /// (re)allocation will be performed only if there is not enough space or the pointer
+    /// alignment is invalid.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::new();
+    ///   let mut num = growable.consume_uninit::<u64>();
+    ///   num.write(42);
+    ///   let num = unsafe { Reusable::assume_init(num) };
+    ///   assert_eq!(*num, 42);
+    /// ```
+    #[inline]
+    pub fn consume_uninit<T>(mut self) -> Reusable<MaybeUninit<T>, A> {
+        if let Err(layout) = self.grow(mem::size_of::<T>(), mem::align_of::<T>(), false) {
+            handle_alloc_error(layout);
+        }
+        unsafe {
+            let ptr = self.ptr.cast();
+            self.into_reusable(ptr)
+        }
+    }
+
+    /// Moves an unsized value out of the [`Box`] into this `Growable`, an actual (re)allocation
+    /// will be performed only if there is not enough space or the pointer alignment is invalid.
+    /// The memory of the [`Box`] is released.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::fmt::Display;
+    ///   let b: Box<dyn Display> = Box::new("Hello World");
+    ///   let v = Growable::new().consume_box(b);
+    ///   assert_eq!(v.to_string(), "Hello World");
+    /// ```
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    #[inline]
+    pub fn consume_box<T, B>(mut self, b: Box<T, B>) -> Reusable<T, A>
+    where
+        T: ?Sized,
+        B: Allocator,
+    {
+        let layout = Layout::for_value::<T>(&b);
+        if let Err(layout) = self.grow(layout.size(), layout.align(), false) {
+            handle_alloc_error(layout);
+        }
+        let (raw, alloc) = Box::into_raw_with_allocator(b);
+        unsafe {
+            ptr::copy_nonoverlapping(raw as *const u8, self.ptr.as_ptr(), layout.size());
+            if layout.size() != 0 {
+                alloc.deallocate(NonNull::new_unchecked(raw as *mut u8), layout);
+            }
+            let ptr = NonNull::from_raw_parts(self.ptr, ptr::metadata(raw));
+            self.into_reusable(ptr)
+        }
+    }
+
+    /// Clones the slice into this `Growable`, an actual (re)allocation will be performed
+    /// only if there is not enough space or the pointer alignment is invalid.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let names = vec![String::from("Foo"), String::from("Bar")];
+    ///   let v = Growable::new().consume_slice_clone(&names);
+    ///   assert_eq!(&*v, &names[..]);
+    /// ```
+    #[inline]
+    pub fn consume_slice_clone<T>(self, slice: &[T]) -> Reusable<[T], A>
+    where
+        T: Clone,
+    {
+        self.consume_slice_from_iter(slice.iter().cloned())
+    }
+
+    /// Collects the iterator into a slice stored in this `Growable`, an actual (re)allocation
+    /// will be performed only if there is not enough space or the pointer alignment is invalid.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler. The slice length is taken from the iterator upfront,
+    /// so if it yields fewer items the slice is shorter and any extra items are never consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let v = Growable::new().consume_slice_from_iter((0 .. 4).map(|i| i * 2));
+    ///   assert_eq!(&*v, &[0, 2, 4, 6]);
+    /// ```
+    pub fn consume_slice_from_iter<T, I>(mut self, iter: I) -> Reusable<[T], A>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        /// Drops already written items if the iterator panics.
+        struct Guard<T> {
+            ptr: *mut T,
+            len: usize,
+        }
+
+        impl<T> Drop for Guard<T> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr, self.len)) };
+            }
+        }
+
+        let iter = iter.into_iter();
+        let len = iter.len();
+        let layout =
+            Layout::array::<T>(len).expect("Growable::consume_slice_from_iter: invalid layout");
+        if let Err(layout) = self.grow(layout.size(), layout.align(), false) {
+            handle_alloc_error(layout);
+        }
+        let ptr = self.ptr.cast::<T>();
+        let mut guard = Guard {
+            ptr: ptr.as_ptr(),
+            len: 0,
+        };
+        for t in iter.take(len) {
+            unsafe { guard.ptr.add(guard.len).write(t) };
+            guard.len += 1;
+        }
+        let len = guard.len;
+        mem::forget(guard);
+        unsafe { self.into_reusable(NonNull::slice_from_raw_parts(ptr, len)) }
+    }
+
+    /// Copies the string into this `Growable`, an actual (re)allocation will be performed
+    /// only if there is not enough space.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let v = Growable::new().consume_str("Hello World");
+    ///   assert_eq!(&*v, "Hello World");
+    /// ```
+    #[inline]
+    pub fn consume_str(mut self, s: &str) -> Reusable<str, A> {
+        if let Err(layout) = self.grow(s.len(), 1, false) {
+            handle_alloc_error(layout);
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr(), self.ptr.as_ptr(), s.len());
+            let ptr = NonNull::slice_from_raw_parts(self.ptr, s.len());
+            self.into_reusable(NonNull::new_unchecked(ptr.as_ptr() as *mut str))
+        }
+    }
+
+    /// Takes the memory of the [`Vec`], its items are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::from_vec(vec![1u32, 2, 3, 4]);
+    ///   assert_eq!(growable.len(), 16);
+    ///   assert_eq!(growable.alignment(), 4);
+    /// ```
+    ///
+    /// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+    pub fn from_vec<T>(mut vec: Vec<T, A>) -> Self {
+        vec.clear();
+        let mut vec = mem::ManuallyDrop::new(vec);
+        let alloc = unsafe { ptr::read(vec.allocator()) };
+        if mem::size_of::<T>() == 0 || vec.capacity() == 0 {
+            return Self::with_capacity_in(0, mem::align_of::<T>(), alloc);
+        }
+        Growable {
+            len: vec.capacity() * mem::size_of::<T>(),
+            ptr_alignment: mem::align_of::<T>(),
+            ptr: unsafe { NonNull::new_unchecked(vec.as_mut_ptr().cast()) },
+            growth_policy: GrowthPolicy::Exact,
+            regrowths: Regrowths::default(),
+            alloc,
+        }
+    }
+
+    /// Turns this `Growable` into an empty [`Vec`] with as much capacity as the memory allows.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler: the memory is reallocated if its size
+    /// is not a multiple of the size of `T` or the alignment does not match exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::with_capacity(16, 4);
+    ///   let mut vec = growable.into_vec::<u32>();
+    ///   assert_eq!(vec.capacity(), 4);
+    ///   vec.extend([1, 2, 3, 4]);
+    ///   let growable = Growable::from_vec(vec);
+    ///   assert_eq!(growable.len(), 16);
+    /// ```
+    ///
+    /// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+    pub fn into_vec<T>(mut self) -> Vec<T, A> {
+        let capacity = self.len.checked_div(mem::size_of::<T>()).unwrap_or(0);
+        if capacity == 0 {
+            let (ptr, layout, alloc) = self.into_raw_parts();
+            if layout.size() != 0 {
+                unsafe { alloc.deallocate(ptr, layout) };
+            }
+            return Vec::new_in(alloc);
+        }
+        let layout = Layout::array::<T>(capacity).expect("Growable::into_vec: invalid layout");
+        if layout.size() != self.len || layout.align() != self.ptr_alignment {
+            if let Err(layout) = self.reallocate(layout, false) {
+                handle_alloc_error(layout);
+            }
+        }
+        let (ptr, _, alloc) = self.into_raw_parts();
+        unsafe { Vec::from_raw_parts_in(ptr.cast().as_ptr(), 0, capacity, alloc) }
+    }
+
+    /// Places an instance of `T` on the heap only if this `Growable` is already
+    /// capable to store it, the allocator is never used.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back along with this `Growable` if there is not enough space
+    /// or the pointer alignment is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let growable = Growable::with_capacity(8, 8);
+    ///   let num = growable.try_reuse(0u64).unwrap();
+    ///   assert_eq!(*num, 0u64);
+    ///   let growable = Reusable::free(num);
+    ///   let (arr, growable) = growable.try_reuse([0u64; 2]).unwrap_err();
+    ///   assert_eq!(growable.len(), 8);
+    /// ```
+    #[inline]
+    pub fn try_reuse<T>(self, t: T) -> Result<Reusable<T, A>, (T, Self)> {
+        if self.fits(mem::size_of::<T>(), mem::align_of::<T>()) {
+            Ok(self.copy(t))
+        } else {
+            Err((t, self))
+        }
+    }
+
+    #[inline]
+    fn fits(&self, len: usize, ptr_alignment: usize) -> bool {
+        self.len >= len && self.ptr_alignment >= ptr_alignment
+    }
+
+    /// Makes sure that this `Growable` is capable to store `len` bytes with the given alignment.
+    ///
+    /// The memory is never copied: the block is expected to be empty, any value it used to store
+    /// was already dropped or moved out, so its stale bytes are of no use. Instead of a `realloc`
+    /// the old block is released and a new one is allocated. If `keep_on_failure` is set the new
+    /// block is allocated first, so on failure this `Growable` is left intact, otherwise the old
+    /// block is released first, giving the allocator a chance to reuse it.
+    fn grow(
+        &mut self,
+        len: usize,
+        ptr_alignment: usize,
+        keep_on_failure: bool,
+    ) -> Result<(), Layout> {
+        // NB: len is valid or zero, ptr_alignment is always valid.
+        if self.fits(len, ptr_alignment) {
+            // No allocation is required.
+            return Ok(());
+        }
+        // The alignment is never lowered, so a block keeps serving the types it served before.
+        let ptr_alignment = cmp::max(self.ptr_alignment, ptr_alignment);
+        if self.len == 0 {
+            // Growing from zero length is the same as a fresh allocation.
+            let len = self.growth_policy.round(len);
+            self.ptr = Self::allocate(&self.alloc, len, ptr_alignment)?;
+            self.len = len;
+            self.ptr_alignment = ptr_alignment;
+            self.regrowths.record();
+            return Ok(());
+        }
+
+        let len = cmp::max(self.len, self.growth_policy.round(len));
+        // NB: Could be a bug if there is a way to define a ZST with align_of() greater than one?!
+        assert_ne!(len, 0, "Growable::grow: realloc to zero");
+        let layout =
+            Layout::from_size_align(len, ptr_alignment).expect("Growable::grow: invalid layout");
+        self.reallocate(layout, keep_on_failure)?;
+        self.regrowths.record();
+        Ok(())
+    }
+
+    /// Replaces a non-empty block with a new one, the contents are not preserved.
+    fn reallocate(&mut self, layout: Layout, keep_on_failure: bool) -> Result<(), Layout> {
+        unsafe {
+            let layout_curr = Layout::from_size_align_unchecked(self.len, self.ptr_alignment);
+            let ptr = if keep_on_failure {
+                let ptr = self.alloc.allocate(layout).map_err(|_| layout)?;
+                self.alloc.deallocate(self.ptr, layout_curr);
+                ptr
+            } else {
+                self.alloc.deallocate(self.ptr, layout_curr);
+                // The old block is gone, so this Growable must stay valid even if allocation fails.
+                self.len = 0;
+                self.ptr = Self::allocate(&self.alloc, 0, self.ptr_alignment)?;
+                self.alloc.allocate(layout).map_err(|_| layout)?
+            };
+            self.len = layout.size();
+            self.ptr_alignment = layout.align();
+            self.ptr = ptr.as_non_null_ptr();
+        }
+        Ok(())
+    }
+
+    /// Shrinks the memory block down to `len` bytes, returns false if the allocator failed.
+    fn shrink(&mut self, len: usize) -> bool {
+        if len >= self.len {
+            return true;
+        }
+        unsafe {
+            let layout_curr = Layout::from_size_align_unchecked(self.len, self.ptr_alignment);
+            if len == 0 {
+                self.alloc.deallocate(self.ptr, layout_curr);
+                self.len = 0;
+                self.ptr = Self::allocate(&self.alloc, 0, self.ptr_alignment)
+                    .expect("Growable::shrink: invalid alignment");
+                return true;
+            }
+            let layout = Layout::from_size_align_unchecked(len, self.ptr_alignment);
+            match self.alloc.shrink(self.ptr, layout_curr, layout) {
+                Ok(ptr) => {
+                    self.len = len;
+                    self.ptr = ptr.as_non_null_ptr();
+                    true
+                },
+                Err(AllocError) => false,
+            }
+        }
+    }
+
+    /// Splits this `Growable` into a pointer, a layout of the block and the allocator.
+    #[inline]
+    fn into_raw_parts(self) -> (NonNull<u8>, Layout, A) {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(this.len, this.ptr_alignment);
+            (this.ptr, layout, ptr::read(&this.alloc))
+        }
+    }
+
+    fn allocate(alloc: &A, len: usize, ptr_alignment: usize) -> Result<NonNull<u8>, Layout> {
+        if len != 0 {
+            let layout = Layout::from_size_align(len, ptr_alignment)
+                .expect("Growable::with_capacity: invalid layout");
+            alloc.allocate(layout).map(|ptr| ptr.as_non_null_ptr()).map_err(|_| layout)
+        } else {
+            assert!(
+                ptr_alignment.is_power_of_two(),
+                "Growable::with_capacity: alignment must be a power of two"
+            );
+            // A dangling pointer still has to be aligned to be used with ZSTs.
+            Ok(unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(ptr_alignment)) })
+        }
+    }
+
+    fn copy<T>(self, t: T) -> Reusable<T, A> {
+        // NB: len is at least equal to size_of::<T>(), ptr_alignment is at least equal to align_of::<T>().
+        unsafe {
+            let ptr = self.ptr.cast::<T>();
+            ptr.as_ptr().write(t);
+            self.into_reusable(ptr)
+        }
+    }
+
+    /// Converts this `Growable` into a [`Reusable`] pointing to the value at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value placed at the beginning of this `Growable`.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub(crate) unsafe fn into_reusable<T>(self, ptr: NonNull<T>) -> Reusable<T, A>
+    where
+        T: ?Sized,
+    {
+        let this = mem::ManuallyDrop::new(self);
+        Reusable {
+            len: this.len,
+            ptr_alignment: this.ptr_alignment,
+            ptr,
+            growth_policy: this.growth_policy,
+            regrowths: this.regrowths,
+            alloc: ptr::read(&this.alloc),
+        }
+    }
+}
+
+/// A reusable box. It behaves just like the default [`Box`] (and it WILL free memory on drop)
+/// but it is also possible to free it manually, fetching a [`Growable`] back.
+///
+/// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+/// [`Growable`]: struct.Growable.html
+pub struct Reusable<T: ?Sized, A: Allocator = Global> {
+    len: usize,
+    ptr_alignment: usize,
+    ptr: NonNull<T>,
+    growth_policy: GrowthPolicy,
+    regrowths: Regrowths,
+    alloc: A,
+}
+
+unsafe impl<T, A> Send for Reusable<T, A>
+where
+    T: Send + ?Sized,
+    A: Allocator + Send,
+{
+}
+
+unsafe impl<T, A> Sync for Reusable<T, A>
+where
+    T: Sync + ?Sized,
+    A: Allocator + Sync,
+{
+}
+
+impl<T, A> Clone for Reusable<T, A>
+where
+    T: Clone,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        let growable = Growable::with_capacity_for_type_in::<T>(self.alloc.clone())
+            .with_growth_policy(self.growth_policy);
+        growable.consume(T::clone(self))
+    }
+}
+
+impl<T, A> ops::Deref for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, A> ops::DerefMut for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, A> fmt::Pointer for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr, formatter)
+    }
+}
+
+impl<T, A> fmt::Debug for Reusable<T, A>
+where
+    T: ?Sized + fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let t: &T = self;
+        fmt::Debug::fmt(t, formatter)
+    }
+}
+
+impl<T, A> Drop for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            if self.len != 0 {
+                self.alloc.deallocate(
+                    self.ptr.cast(),
+                    Layout::from_size_align_unchecked(self.len, self.ptr_alignment),
+                );
+            }
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<Reusable<U, A>> for Reusable<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator,
+{
+}
+
+impl<T, A> PartialEq for Reusable<T, A>
+where
+    T: ?Sized + PartialEq,
+    A: Allocator,
+{
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<T, A> Eq for Reusable<T, A>
+where
+    T: ?Sized + Eq,
+    A: Allocator,
+{
+}
+
+impl<T, A> PartialOrd for Reusable<T, A>
+where
+    T: ?Sized + PartialOrd,
+    A: Allocator,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        PartialOrd::lt(&**self, &**other)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        PartialOrd::le(&**self, &**other)
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        PartialOrd::gt(&**self, &**other)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        PartialOrd::ge(&**self, &**other)
+    }
+}
+
+impl<T, A> Ord for Reusable<T, A>
+where
+    T: ?Sized + Ord,
+    A: Allocator,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, A> Hash for Reusable<T, A>
+where
+    T: ?Sized + Hash,
+    A: Allocator,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        (**self).hash(state);
+    }
+}
+
+impl<T, A> Hasher for Reusable<T, A>
+where
+    T: ?Sized + Hasher,
+    A: Allocator,
+{
+    fn finish(&self) -> u64 {
+        (**self).finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        (**self).write(bytes)
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        (**self).write_u8(i)
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        (**self).write_u16(i)
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        (**self).write_u32(i)
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        (**self).write_u64(i)
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        (**self).write_u128(i)
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        (**self).write_usize(i)
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        (**self).write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        (**self).write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        (**self).write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        (**self).write_i64(i)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        (**self).write_i128(i)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        (**self).write_isize(i)
+    }
+}
+
+impl<T, A> fmt::Display for Reusable<T, A>
+where
+    T: ?Sized + fmt::Display,
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Borrow<T> for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T, A> BorrowMut<T> for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T, A> AsRef<T> for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T, A> AsMut<T> for Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<I, A> Iterator for Reusable<I, A>
+where
+    I: ?Sized + Iterator,
+    A: Allocator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        (**self).next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<I::Item> {
+        (**self).nth(n)
+    }
+}
+
+impl<I, A> DoubleEndedIterator for Reusable<I, A>
+where
+    I: ?Sized + DoubleEndedIterator,
+    A: Allocator,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        (**self).next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<I::Item> {
+        (**self).nth_back(n)
+    }
+}
+
+impl<I, A> ExactSizeIterator for Reusable<I, A>
+where
+    I: ?Sized + ExactSizeIterator,
+    A: Allocator,
+{
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<I, A> FusedIterator for Reusable<I, A>
+where
+    I: ?Sized + FusedIterator,
+    A: Allocator,
+{
+}
+
+impl<R, A> io::Read for Reusable<R, A>
+where
+    R: ?Sized + io::Read,
+    A: Allocator,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read(buf)
     }
 
-    /// Returns a new instance of `Growable` with memory already allocated on the heap suitable to
-    /// store an instance of a given type T.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use growable::*;
-    ///   struct Foo {
-    ///       a: u8,
-    ///       b: usize,
-    ///       c: (),
-    ///   }
-    ///   let _ = Growable::with_capacity_for_type::<Foo>();
-    /// ```
-    ///
-    /// [`Growable`]: struct.Growable.html
-    #[inline]
-    pub fn with_capacity_for_type<T>() -> Self {
-        Self::with_capacity(mem::size_of::<T>(), mem::align_of::<T>())
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        (**self).read_vectored(bufs)
     }
 
-    /// Returns a new instance of `Growable` with memory already allocated on the heap.
-    ///
-    /// # Panics
-    ///
-    /// * `ptr_alignment` is not a power of two.
-    /// * `len` overflows after being rounded up to the nearest multiple of the alignment.
-    ///
-    /// # Notes
-    ///
-    /// Might trigger `alloc_error` handler.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use growable::*;
-    ///   let _ = Growable::with_capacity(256, 16);
-    /// ```
-    ///
-    /// [`Growable`]: struct.Growable.html
-    #[inline]
-    pub fn with_capacity(len: usize, ptr_alignment: usize) -> Self {
-        let ptr = if len != 0 {
-            let layout = Layout::from_size_align(len, ptr_alignment)
-                .expect("Growable::with_capacity: invalid layout");
-            Global
-                .allocate(layout)
-                .map_or_else(|_| handle_alloc_error(layout), |ptr| ptr.as_non_null_ptr())
-        } else {
-            assert!(
-                ptr_alignment.is_power_of_two(),
-                "Growable::with_capacity: alignment must be a power of two"
-            );
-            NonNull::<u8>::dangling()
-        };
-        Growable {
-            len,
-            ptr_alignment,
-            ptr,
-        }
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (**self).read_to_end(buf)
     }
 
-    /// Returns true if no memory has been allocated yet.
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        (**self).read_to_string(buf)
     }
 
-    /// Returns the amount of memory allocated by this `Growable`.
-    ///
-    /// [`Growable`]: struct.Growable.html
-    #[inline]
-    pub fn len(&self) -> usize {
-        self.len
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_exact(buf)
     }
+}
 
-    /// Returns the alignment.
-    #[inline]
-    pub fn alignment(&self) -> usize {
-        self.ptr_alignment
+impl<W, A> io::Write for Reusable<W, A>
+where
+    W: ?Sized + io::Write,
+    A: Allocator,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
     }
 
-    /// Places an instance of `T` on the heap, an actual (re)allocation will be performed
-    /// only if there is not enough space or the pointer alignment is invalid.
-    ///
-    /// # Notes
-    ///
-    /// Might trigger `alloc_error` handler.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use growable::*;
-    ///   let growable = Growable::with_capacity(128, 8);
-    ///   let num = growable.consume(0usize);
-    ///   assert_eq!(*num, 0usize);
-    /// ```
-    #[inline]
-    pub fn consume<T>(mut self, t: T) -> Reusable<T> {
-        self.grow(mem::size_of::<T>(), mem::align_of::<T>());
-        self.copy(t)
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        (**self).write_vectored(bufs)
     }
 
-    fn grow(&mut self, len: usize, ptr_alignment: usize) {
-        // NB: len is valid or zero, ptr_alignment is always valid.
-        if self.len == 0 {
-            // Growing from zero length can be done with Growable::with_capacity call.
-            *self = Self::with_capacity(len, ptr_alignment);
-            return;
-        }
-        if self.len >= len && self.ptr_alignment >= ptr_alignment {
-            // No allocation is required.
-            return;
-        }
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
 
-        let len = cmp::max(self.len, len);
-        // NB: Could be a bug if there is a way to define a ZST with align_of() greater than one?!
-        assert_ne!(len, 0, "Growable::grow: realloc to zero");
-        unsafe {
-            let layout_curr = Layout::from_size_align_unchecked(self.len, self.ptr_alignment);
-            let layout = Layout::from_size_align_unchecked(len, ptr_alignment);
-            // If the alignment is the same we can try to grow in place.
-            let ptr = if layout.align() == layout_curr.align() {
-                Global.grow(self.ptr, layout_curr, layout)
-            } else {
-                // Oops, a reallocation is required.
-                Global.deallocate(self.ptr, layout_curr);
-                Global.allocate(layout)
-            }
-            .map_or_else(|_| handle_alloc_error(layout), |ptr| ptr.as_non_null_ptr());
-            self.len = len;
-            self.ptr_alignment = ptr_alignment;
-            self.ptr = ptr;
-        }
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        (**self).write_all(buf)
     }
 
-    fn copy<T>(self, t: T) -> Reusable<T> {
-        // NB: len is at least equal to size_of::<T>(), ptr_alignment is at least equal to align_of::<T>().
-        let result = unsafe {
-            let ptr_raw = self.ptr.cast::<T>().as_ptr();
-            ptr_raw.write(t);
-            let ptr = NonNull::new_unchecked(ptr_raw);
-            Reusable {
-                len: self.len,
-                ptr_alignment: self.ptr_alignment,
-                ptr,
-            }
-        };
-        mem::forget(self);
-        result
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        (**self).write_fmt(fmt)
     }
 }
 
-/// A reusable box. It behaves just like the default [`Box`] (and it WILL free memory on drop)
-/// but it is also possible to free it manually, fetching a [`Growable`] back.
-///
-/// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
-/// [`Growable`]: struct.Growable.html
-pub struct Reusable<T: ?Sized> {
-    len: usize,
-    ptr_alignment: usize,
-    ptr: NonNull<T>,
-}
+impl<B, A> io::BufRead for Reusable<B, A>
+where
+    B: ?Sized + io::BufRead,
+    A: Allocator,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
 
-unsafe impl<T> Send for Reusable<T> where T: Send + ?Sized {}
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (**self).read_until(byte, buf)
+    }
 
-unsafe impl<T> Sync for Reusable<T> where T: Sync + ?Sized {}
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        (**self).read_line(buf)
+    }
+}
 
-impl<T> Clone for Reusable<T>
+impl<S, A> io::Seek for Reusable<S, A>
 where
-    T: ?Sized + Clone,
+    S: ?Sized + io::Seek,
+    A: Allocator,
 {
-    fn clone(&self) -> Self {
-        let growable = Growable::with_capacity_for_type::<T>();
-        growable.consume(T::clone(self))
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        (**self).seek(pos)
     }
-}
 
-impl<T: ?Sized> ops::Deref for Reusable<T> {
-    type Target = T;
+    fn stream_position(&mut self) -> io::Result<u64> {
+        (**self).stream_position()
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { self.ptr.as_ref() }
+impl<T, A> Error for Reusable<T, A>
+where
+    T: Error,
+    A: Allocator,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Error::source(&**self)
     }
 }
 
-impl<T: ?Sized> ops::DerefMut for Reusable<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { self.ptr.as_mut() }
+impl<'a, E> From<E> for Reusable<dyn Error + 'a>
+where
+    E: Error + 'a,
+{
+    /// Moves the error into a newly allocated [`Reusable`], just like [`Box`] does.
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    fn from(e: E) -> Self {
+        Growable::new().consume(e)
     }
 }
 
-impl<T> fmt::Pointer for Reusable<T>
+impl<'a, E> From<E> for Reusable<dyn Error + Send + Sync + 'a>
 where
-    T: ?Sized,
+    E: Error + Send + Sync + 'a,
 {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Pointer::fmt(&self.ptr, formatter)
+    /// Moves the error into a newly allocated [`Reusable`], just like [`Box`] does.
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    fn from(e: E) -> Self {
+        Growable::new().consume(e)
     }
 }
 
-impl<T> fmt::Debug for Reusable<T>
+impl<T, A> From<Reusable<T, A>> for Growable<A>
 where
-    T: ?Sized + fmt::Debug,
+    T: ?Sized,
+    A: Allocator,
 {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        let t: &T = self;
-        fmt::Debug::fmt(t, formatter)
+    /// Drops the value and returns the memory back, see [`Reusable::free`].
+    ///
+    /// [`Reusable::free`]: struct.Reusable.html#method.free
+    #[inline]
+    fn from(t: Reusable<T, A>) -> Self {
+        Reusable::free(t)
     }
 }
 
-impl<T: ?Sized> Drop for Reusable<T> {
-    fn drop(&mut self) {
-        self.free_in_place();
+impl<T, A> From<Box<T, A>> for Growable<A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
+    /// Drops the value and takes the memory of the [`Box`].
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    #[inline]
+    fn from(b: Box<T, A>) -> Self {
+        Reusable::free(Reusable::from_box(b))
     }
 }
 
-impl<T, U> CoerceUnsized<Reusable<U>> for Reusable<T>
+impl<T, A> From<Vec<T, A>> for Growable<A>
 where
-    T: ?Sized + Unsize<U>,
-    U: ?Sized,
+    A: Allocator,
 {
+    /// Drops the items and takes the memory of the [`Vec`], see [`Growable::from_vec`].
+    ///
+    /// [`Growable::from_vec`]: struct.Growable.html#method.from_vec
+    /// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+    #[inline]
+    fn from(vec: Vec<T, A>) -> Self {
+        Growable::from_vec(vec)
+    }
 }
 
-impl<T: ?Sized> Reusable<T> {
+impl<T, A> Reusable<T, A>
+where
+    T: ?Sized,
+    A: Allocator,
+{
     /// Drops the value and returns the memory back as a [`Growable`].
     ///
     /// [`Growable`]: struct.Growable.html
     #[inline]
-    pub fn free(mut this: Self) -> Growable {
-        let growable = this.free_in_place();
-        mem::forget(this);
-        growable
+    pub fn free(this: Self) -> Growable<A> {
+        unsafe {
+            ptr::drop_in_place(this.ptr.as_ptr());
+            Self::into_growable(this)
+        }
     }
 
     /// Moves the value out of this [`Reusable`] without dropping it and then
@@ -642,35 +2795,207 @@ impl<T: ?Sized> Reusable<T> {
     /// [`Growable`]: struct.Growable.html
     /// [`Reusable`]: struct.Reusable.html
     #[inline]
-    pub fn free_move(this: Self) -> (T, Growable)
+    pub fn free_move(this: Self) -> (T, Growable<A>)
     where
         T: Sized,
     {
         unsafe {
             let t = ptr::read(this.ptr.as_ptr());
-            let growable = Growable {
-                len: this.len,
-                ptr_alignment: this.ptr_alignment,
-                ptr: this.ptr.cast(),
-            };
-            mem::forget(this);
-            (t, growable)
+            (t, Self::into_growable(this))
         }
     }
 
-    #[inline]
-    fn free_in_place(&mut self) -> Growable {
+    /// Converts this `Reusable` into a [`Box`].
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler: the memory is reallocated if the value does not
+    /// occupy it entirely or the alignment does not match exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let v = Growable::with_capacity(64, 8).consume(42u32);
+    ///   let b: Box<u32> = Reusable::into_box(v);
+    ///   assert_eq!(*b, 42);
+    /// ```
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    pub fn into_box(this: Self) -> Box<T, A> {
+        let layout = Layout::for_value::<T>(&this);
+        let (ptr, growable) = Self::into_raw_parts(this);
+        let (ptr_curr, layout_curr, alloc) = growable.into_raw_parts();
+        if layout == layout_curr {
+            return unsafe { Box::from_raw_in(ptr.as_ptr(), alloc) };
+        }
         unsafe {
-            ptr::drop_in_place(self.ptr.as_ptr());
-            Growable {
-                len: self.len,
-                ptr_alignment: self.ptr_alignment,
-                ptr: self.ptr.cast(),
+            let ptr_new = Growable::allocate(&alloc, layout.size(), layout.align())
+                .unwrap_or_else(|layout| handle_alloc_error(layout));
+            ptr::copy_nonoverlapping(ptr_curr.as_ptr(), ptr_new.as_ptr(), layout.size());
+            if layout_curr.size() != 0 {
+                alloc.deallocate(ptr_curr, layout_curr);
             }
+            let ptr = ptr::from_raw_parts_mut(ptr_new.as_ptr(), ptr::metadata(ptr.as_ptr()));
+            Box::from_raw_in(ptr, alloc)
+        }
+    }
+
+    /// Converts a [`Box`] into a `Reusable`, the memory of the [`Box`] is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::fmt::Debug;
+    ///   let b: Box<dyn Debug> = Box::new(42u32);
+    ///   let v = Reusable::from_box(b);
+    ///   assert_eq!(format!("{:?}", v), "42");
+    ///   let growable = Reusable::free(v);
+    ///   assert_eq!(growable.len(), 4);
+    /// ```
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    pub fn from_box(b: Box<T, A>) -> Self {
+        let layout = Layout::for_value::<T>(&b);
+        let (ptr, alloc) = Box::into_raw_with_allocator(b);
+        Reusable {
+            len: layout.size(),
+            ptr_alignment: layout.align(),
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+            growth_policy: GrowthPolicy::Exact,
+            regrowths: Regrowths::default(),
+            alloc,
         }
     }
+
+    /// Returns a reference to the underlying allocator.
+    ///
+    /// # Notes
+    ///
+    /// This is an associated function that needs to be used as `Reusable::allocator(&r)`.
+    #[inline]
+    pub fn allocator(this: &Self) -> &A {
+        &this.alloc
+    }
+
+    /// Converts this `Reusable` into a `Growable` without touching the value.
+    ///
+    /// # Safety
+    ///
+    /// The value must be already dropped or moved out.
+    #[inline]
+    unsafe fn into_growable(this: Self) -> Growable<A> {
+        Self::into_raw_parts(this).1
+    }
+
+    /// Splits this `Reusable` into a pointer to the value and a `Growable` owning its memory.
+    #[inline]
+    pub(crate) fn into_raw_parts(this: Self) -> (NonNull<T>, Growable<A>) {
+        let this = mem::ManuallyDrop::new(this);
+        let growable = Growable {
+            len: this.len,
+            ptr_alignment: this.ptr_alignment,
+            ptr: this.ptr.cast(),
+            growth_policy: this.growth_policy,
+            regrowths: this.regrowths,
+            alloc: unsafe { ptr::read(&this.alloc) },
+        };
+        (this.ptr, growable)
+    }
+
+    /// Converts to `Reusable<U, A>`, the memory is kept as is.
+    ///
+    /// # Safety
+    ///
+    /// The value must be of type `U`.
+    #[inline]
+    unsafe fn downcast_unchecked<U>(this: Self) -> Reusable<U, A> {
+        let (ptr, growable) = Self::into_raw_parts(this);
+        growable.into_reusable(ptr.cast())
+    }
+}
+
+impl<T, A> Reusable<MaybeUninit<T>, A>
+where
+    A: Allocator,
+{
+    /// Converts to `Reusable<T, A>`.
+    ///
+    /// # Safety
+    ///
+    /// The value must be initialized, see [`MaybeUninit::assume_init`].
+    ///
+    /// [`MaybeUninit::assume_init`]: https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#method.assume_init
+    #[inline]
+    pub unsafe fn assume_init(this: Self) -> Reusable<T, A> {
+        let (ptr, growable) = Self::into_raw_parts(this);
+        growable.into_reusable(ptr.cast())
+    }
+
+    /// Writes the value and converts to `Reusable<T, A>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let num = Growable::new().consume_uninit::<u64>();
+    ///   let num = Reusable::write(num, 42);
+    ///   assert_eq!(*num, 42);
+    /// ```
+    #[inline]
+    pub fn write(mut this: Self, value: T) -> Reusable<T, A> {
+        this.write(value);
+        unsafe { Self::assume_init(this) }
+    }
+}
+
+/// Implements `downcast` for a `Reusable` of the given `dyn Any` trait object.
+macro_rules! impl_downcast {
+    ($($any:tt)+) => {
+        impl<A> Reusable<$($any)+, A>
+        where
+            A: Allocator,
+        {
+            /// Attempts to downcast the value to a concrete type, the memory is kept as is.
+            ///
+            /// # Errors
+            ///
+            /// Returns the same `Reusable` back if the value is not of type `T`.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// # use growable::*;
+            /// # use std::any::Any;
+            #[doc = concat!(
+                "  let any: Reusable<",
+                stringify!($($any)+),
+                "> = Growable::new().consume(42u32);"
+            )]
+            ///   let any = any.downcast::<String>().unwrap_err();
+            ///   let num = any.downcast::<u32>().unwrap();
+            ///   assert_eq!(*num, 42);
+            /// ```
+            #[inline]
+            pub fn downcast<T>(self) -> Result<Reusable<T, A>, Self>
+            where
+                T: Any,
+            {
+                if (*self).is::<T>() {
+                    Ok(unsafe { Self::downcast_unchecked(self) })
+                } else {
+                    Err(self)
+                }
+            }
+        }
+    };
 }
 
+impl_downcast!(dyn Any);
+impl_downcast!(dyn Any + Send);
+impl_downcast!(dyn Any + Send + Sync);
+
 /// Replaces the value, dropping the old one but not the memory associated with it.
 ///
 /// # Notes
@@ -680,9 +3005,10 @@ impl<T: ?Sized> Reusable<T> {
 /// [`Reusable::free`]: struct.Reusable.html#method.free
 /// [`Growable::consume`]: struct.Growable.html#method.consume
 #[inline]
-pub fn replace<T, U>(this: Reusable<T>, u: U) -> Reusable<U>
+pub fn replace<T, U, A>(this: Reusable<T, A>, u: U) -> Reusable<U, A>
 where
     T: ?Sized,
+    A: Allocator,
 {
     Reusable::free(this).consume(u)
 }
@@ -0,0 +1,149 @@
+use crate::{Growable, GrowablePool, GrowablePoolBuilder, Reusable};
+use std::{
+    cell::RefCell,
+    mem,
+    sync::{Mutex, PoisonError},
+};
+
+thread_local! {
+    static POOL: RefCell<Option<GrowablePool>> = const { RefCell::new(None) };
+}
+
+static BUILDER: Mutex<Option<GrowablePoolBuilder>> = Mutex::new(None);
+
+/// Calls the closure with the default pool of the current thread, building it if needed.
+/// Returns `None` if the pool is already borrowed or destroyed along with the thread.
+fn with_pool<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&mut GrowablePool) -> R,
+{
+    POOL.try_with(|pool| {
+        let mut pool = pool.try_borrow_mut().ok()?;
+        let pool = pool.get_or_insert_with(|| {
+            let builder = BUILDER.lock().unwrap_or_else(PoisonError::into_inner);
+            builder.as_ref().map_or_else(GrowablePool::new, GrowablePoolBuilder::build)
+        });
+        Some(f(pool))
+    })
+    .ok()
+    .flatten()
+}
+
+impl GrowablePoolBuilder {
+    /// Makes this builder the one used to lazily build the default pool of each thread,
+    /// threads that have built their default pool already keep it.
+    ///
+    /// # Notes
+    ///
+    /// Supposed to be called once at startup, see [`GrowablePool::replace_local`]
+    /// to swap the default pool of the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   GrowablePool::builder().with_capacity(64).make_default();
+    ///   let num = Reusable::new(42);
+    ///   assert_eq!(GrowablePool::with_local(|pool| pool.len()), 63);
+    ///   Reusable::recycle(num);
+    /// ```
+    ///
+    /// [`GrowablePool::replace_local`]: struct.GrowablePool.html#method.replace_local
+    pub fn make_default(&self) {
+        *BUILDER.lock().unwrap_or_else(PoisonError::into_inner) = Some(self.clone());
+    }
+}
+
+impl GrowablePool {
+    /// Calls the closure with the default pool of the current thread, building it if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within the closure, or while the thread is being destroyed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let num = GrowablePool::with_local(|pool| pool.allocate(42));
+    ///   assert_eq!(*num, 42);
+    /// ```
+    pub fn with_local<F, R>(f: F) -> R
+    where
+        F: FnOnce(&mut GrowablePool) -> R,
+    {
+        with_pool(f).expect("GrowablePool::with_local: the pool is not available")
+    }
+
+    /// Replaces the default pool of the current thread, returning the previous one
+    /// if it was built already.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within [`GrowablePool::with_local`],
+    /// or while the thread is being destroyed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let pool = GrowablePool::builder().with_capacity(8).build();
+    ///   GrowablePool::replace_local(pool);
+    ///   assert_eq!(GrowablePool::with_local(|pool| pool.len()), 8);
+    /// ```
+    ///
+    /// [`GrowablePool::with_local`]: struct.GrowablePool.html#method.with_local
+    pub fn replace_local(pool: GrowablePool) -> Option<GrowablePool> {
+        POOL.with(|local| local.borrow_mut().replace(pool))
+    }
+}
+
+impl<T> Reusable<T> {
+    /// Allocates a new [`Reusable`] from the default pool of the current thread,
+    /// a drop-in replacement for [`Box::new`].
+    ///
+    /// # Notes
+    ///
+    /// Falls back to a plain allocation if the default pool is not available,
+    /// e.g. if called from within [`GrowablePool::with_local`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let num = Reusable::new(42);
+    ///   assert_eq!(*num, 42);
+    ///   Reusable::recycle(num);
+    /// ```
+    ///
+    /// [`Box::new`]: https://doc.rust-lang.org/std/boxed/struct.Box.html#method.new
+    /// [`GrowablePool::with_local`]: struct.GrowablePool.html#method.with_local
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn new(t: T) -> Self {
+        match with_pool(|pool| pool.take(mem::size_of::<T>(), mem::align_of::<T>())) {
+            Some(growable) => growable.consume(t),
+            None => Growable::with_capacity_for_type::<T>().consume(t),
+        }
+    }
+}
+
+impl<T> Reusable<T>
+where
+    T: ?Sized,
+{
+    /// Drops the value and returns the memory back to the default pool of the current thread.
+    ///
+    /// # Notes
+    ///
+    /// The memory is deallocated if the default pool is not available,
+    /// e.g. if called from within [`GrowablePool::with_local`].
+    ///
+    /// [`GrowablePool::with_local`]: struct.GrowablePool.html#method.with_local
+    #[inline]
+    pub fn recycle(this: Self) {
+        // NB: The value is dropped first, its destructor might want the pool as well.
+        let growable = Reusable::free(this);
+        with_pool(|pool| pool.free(growable));
+    }
+}
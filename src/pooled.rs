@@ -0,0 +1,301 @@
+use crate::{Growable, GrowablePool, Reusable, SyncGrowablePool};
+use std::{
+    alloc::{Allocator, Global},
+    cell::RefCell,
+    fmt,
+    marker::Unsize,
+    mem::ManuallyDrop,
+    ops::{self, CoerceUnsized},
+    ptr::{self, NonNull},
+    rc::{self, Rc},
+    sync::{Arc, Weak},
+};
+
+/// A [`Reusable`] that remembers the [`SyncGrowablePool`] it was allocated from and
+/// goes back to it on drop, the same way [`SyncGrowablePool::free`] does.
+///
+/// # Notes
+///
+/// The pool is referenced weakly, if it is already gone the memory is deallocated instead.
+/// See [`LocalPooled`] for a single-threaded [`GrowablePool`].
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::sync::Arc;
+///   let pool = Arc::new(GrowablePool::builder().with_capacity(1).build_shared());
+///   let num = pool.allocate_pooled(42u32);
+///   assert_eq!(*num, 42);
+///   assert_eq!(pool.len(), 0);
+///   drop(num);
+///   assert_eq!(pool.len(), 1);
+/// ```
+///
+/// [`GrowablePool`]: struct.GrowablePool.html
+/// [`LocalPooled`]: struct.LocalPooled.html
+/// [`Reusable`]: struct.Reusable.html
+/// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+/// [`SyncGrowablePool::free`]: struct.SyncGrowablePool.html#method.free
+pub struct Pooled<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<T>,
+    growable: ManuallyDrop<Growable<A>>,
+    pool: Weak<SyncGrowablePool<A>>,
+}
+
+unsafe impl<T, A> Send for Pooled<T, A>
+where
+    T: Send + ?Sized,
+    A: Allocator + Clone + Send,
+{
+}
+
+// NB: `Pooled::pool` upgrades the weak reference through a shared one, so the pool along with
+// its allocator might be dropped on any thread holding it, same as for `Arc<T>: Sync`.
+unsafe impl<T, A> Sync for Pooled<T, A>
+where
+    T: Sync + ?Sized,
+    A: Allocator + Clone + Send + Sync,
+{
+}
+
+impl<T, A> ops::Deref for Pooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, A> ops::DerefMut for Pooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, A> fmt::Pointer for Pooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr, formatter)
+    }
+}
+
+impl<T, A> fmt::Debug for Pooled<T, A>
+where
+    T: fmt::Debug + ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Drop for Pooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        let reusable = unsafe { ManuallyDrop::take(&mut self.growable).into_reusable(self.ptr) };
+        match self.pool.upgrade() {
+            Some(pool) => pool.free(reusable),
+            None => drop(reusable),
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<Pooled<U, A>> for Pooled<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> Pooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Attaches the [`Reusable`] to the pool so it goes back there on drop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::sync::Arc;
+    ///   let pool = Arc::new(SyncGrowablePool::new());
+    ///   let num = Growable::new().consume(42u32);
+    ///   drop(Pooled::from_reusable(num, &pool));
+    ///   assert_eq!(pool.len(), 1);
+    /// ```
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn from_reusable(reusable: Reusable<T, A>, pool: &Arc<SyncGrowablePool<A>>) -> Self {
+        let (ptr, growable) = Reusable::into_raw_parts(reusable);
+        Pooled {
+            ptr,
+            growable: ManuallyDrop::new(growable),
+            pool: Arc::downgrade(pool),
+        }
+    }
+
+    /// Detaches this handle from the pool, returning a plain [`Reusable`].
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn into_reusable(this: Self) -> Reusable<T, A> {
+        let mut this = ManuallyDrop::new(this);
+        unsafe {
+            drop(ptr::read(&this.pool));
+            ManuallyDrop::take(&mut this.growable).into_reusable(this.ptr)
+        }
+    }
+
+    /// Returns the pool this handle goes back to, if it is still alive.
+    pub fn pool(this: &Self) -> Option<Arc<SyncGrowablePool<A>>> {
+        this.pool.upgrade()
+    }
+}
+
+/// A [`Reusable`] that remembers the single-threaded [`GrowablePool`] it was allocated from
+/// and goes back to it on drop, the same way [`GrowablePool::free`] does.
+///
+/// # Notes
+///
+/// The pool is referenced weakly, if it is already gone or borrowed at the moment
+/// the memory is deallocated instead.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::{cell::RefCell, rc::Rc};
+///   let pool = Rc::new(RefCell::new(GrowablePool::builder().with_capacity(1).build()));
+///   let num = pool.borrow_mut().allocate(42u32);
+///   let num = LocalPooled::from_reusable(num, &pool);
+///   assert_eq!(*num, 42);
+///   assert_eq!(pool.borrow().len(), 0);
+///   drop(num);
+///   assert_eq!(pool.borrow().len(), 1);
+/// ```
+///
+/// [`GrowablePool`]: struct.GrowablePool.html
+/// [`GrowablePool::free`]: struct.GrowablePool.html#method.free
+/// [`Reusable`]: struct.Reusable.html
+pub struct LocalPooled<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<T>,
+    growable: ManuallyDrop<Growable<A>>,
+    pool: rc::Weak<RefCell<GrowablePool<A>>>,
+}
+
+impl<T, A> ops::Deref for LocalPooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, A> ops::DerefMut for LocalPooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, A> fmt::Pointer for LocalPooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr, formatter)
+    }
+}
+
+impl<T, A> fmt::Debug for LocalPooled<T, A>
+where
+    T: fmt::Debug + ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Drop for LocalPooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        let reusable = unsafe { ManuallyDrop::take(&mut self.growable).into_reusable(self.ptr) };
+        // NB: The value is dropped first, its destructor might want the pool as well.
+        let growable = Reusable::free(reusable);
+        if let Some(pool) = self.pool.upgrade() {
+            if let Ok(mut pool) = pool.try_borrow_mut() {
+                pool.free(growable);
+            }
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<LocalPooled<U, A>> for LocalPooled<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> LocalPooled<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Attaches the [`Reusable`] to the pool so it goes back there on drop.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn from_reusable(reusable: Reusable<T, A>, pool: &Rc<RefCell<GrowablePool<A>>>) -> Self {
+        let (ptr, growable) = Reusable::into_raw_parts(reusable);
+        LocalPooled {
+            ptr,
+            growable: ManuallyDrop::new(growable),
+            pool: Rc::downgrade(pool),
+        }
+    }
+
+    /// Detaches this handle from the pool, returning a plain [`Reusable`].
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn into_reusable(this: Self) -> Reusable<T, A> {
+        let mut this = ManuallyDrop::new(this);
+        unsafe {
+            drop(ptr::read(&this.pool));
+            ManuallyDrop::take(&mut this.growable).into_reusable(this.ptr)
+        }
+    }
+
+    /// Returns the pool this handle goes back to, if it is still alive.
+    pub fn pool(this: &Self) -> Option<Rc<RefCell<GrowablePool<A>>>> {
+        this.pool.upgrade()
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{
+    shared::{free_to, PoolHook},
+    Growable, Reusable,
+};
+use std::{
+    alloc::{Allocator, Global},
+    cell::Cell,
+    fmt,
+    marker::{PhantomData, Unsize},
+    mem::{self, ManuallyDrop},
+    ops::{self, CoerceUnsized},
+    process,
+    ptr::{self, NonNull},
+};
+
+/// The limit for both reference counts, going above it aborts so that
+/// a `mem::forget` in a loop can not wrap a count around.
+pub(crate) const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+/// The memory layout of a [`ReusableRc`], the header lives in the same [`Growable`] as the value.
+#[repr(C)]
+pub(crate) struct RcBox<T: ?Sized, A: Allocator + Clone> {
+    strong: Cell<usize>,
+    weak: Cell<usize>,
+    growable: ManuallyDrop<Growable<A>>,
+    pool: PoolHook<A>,
+    value: T,
+}
+
+/// A single-threaded reference-counted [`Reusable`], the reference counts are kept
+/// within the [`Growable`] along with the value.
+///
+/// # Notes
+///
+/// Once the last reference is gone the memory goes back to the [`SyncGrowablePool`]
+/// it was allocated from with [`SyncGrowablePool::allocate_rc`], it is deallocated otherwise.
+/// Use [`ReusableRc::try_unwrap`] or [`ReusableRc::into_growable`] to fetch it back manually.
+///
+/// Unlike the standard library counterpart, a [`WeakReusableRc`] keeps the whole memory
+/// block alive rather than just the reference counts. As long as any weak reference exists
+/// [`ReusableRc::try_unwrap`] fails even for the last strong reference and
+/// [`ReusableRc::into_growable`] drops the value but returns nothing, the memory then goes back
+/// to the pool (or is deallocated) once the last weak reference is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+///   let mut pool = GrowablePool::new();
+///   let rc = Growable::new().consume_rc(42u32);
+///   let other = ReusableRc::clone(&rc);
+///   assert!(ReusableRc::into_growable(rc).is_none());
+///   let growable = ReusableRc::into_growable(other).unwrap();
+///   pool.free(growable);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`Reusable`]: struct.Reusable.html
+/// [`ReusableRc::into_growable`]: struct.ReusableRc.html#method.into_growable
+/// [`ReusableRc::try_unwrap`]: struct.ReusableRc.html#method.try_unwrap
+/// [`SyncGrowablePool`]: struct.SyncGrowablePool.html
+/// [`SyncGrowablePool::allocate_rc`]: struct.SyncGrowablePool.html#method.allocate_rc
+/// [`WeakReusableRc`]: struct.WeakReusableRc.html
+pub struct ReusableRc<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<RcBox<T, A>>,
+    phantom: PhantomData<RcBox<T, A>>,
+}
+
+/// A weak reference to a [`ReusableRc`], it keeps the memory but not the value alive.
+///
+/// [`ReusableRc`]: struct.ReusableRc.html
+pub struct WeakReusableRc<T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<RcBox<T, A>>,
+}
+
+impl<T, A> Clone for ReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        let inner = self.inner();
+        increment(&inner.strong);
+        ReusableRc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A> ops::Deref for ReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner().value
+    }
+}
+
+impl<T, A> fmt::Pointer for ReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&(&**self as *const T), formatter)
+    }
+}
+
+impl<T, A> fmt::Debug for ReusableRc<T, A>
+where
+    T: fmt::Debug + ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Drop for ReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some((growable, pool)) = unsafe { self.release() } {
+            free_to(pool, growable);
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<ReusableRc<U, A>> for ReusableRc<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> ReusableRc<T, A>
+where
+    A: Allocator + Clone,
+{
+    /// Moves the value into the `Growable` along with the reference counts.
+    pub(crate) fn new_in(growable: Growable<A>, t: T, pool: PoolHook<A>) -> Self {
+        let (ptr, growable) = Reusable::into_raw_parts(growable.consume_uninit::<RcBox<T, A>>());
+        let ptr = ptr.cast::<RcBox<T, A>>();
+        unsafe {
+            ptr.as_ptr().write(RcBox {
+                strong: Cell::new(1),
+                weak: Cell::new(1),
+                growable: ManuallyDrop::new(growable),
+                pool,
+                value: t,
+            });
+        }
+        ReusableRc {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A> ReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Creates a new [`WeakReusableRc`] to the same value.
+    ///
+    /// [`WeakReusableRc`]: struct.WeakReusableRc.html
+    pub fn downgrade(this: &Self) -> WeakReusableRc<T, A> {
+        let inner = this.inner();
+        increment(&inner.weak);
+        WeakReusableRc {
+            ptr: this.ptr,
+        }
+    }
+
+    /// Returns the number of strong references to the value.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.get()
+    }
+
+    /// Returns the number of weak references to the value.
+    pub fn weak_count(this: &Self) -> usize {
+        this.inner().weak.get() - 1
+    }
+
+    /// Returns `true` if both references point to the same value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    /// Returns a mutable reference to the value if there are no other references to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Self::is_unique(this) {
+            Some(unsafe { &mut (*this.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as a [`Reusable`] if there are no other references to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same `ReusableRc` back if there are other strong or weak references.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let rc = Growable::new().consume_rc(42u32);
+    ///   let weak = ReusableRc::downgrade(&rc);
+    ///   let rc = ReusableRc::try_unwrap(rc).unwrap_err();
+    ///   drop(weak);
+    ///   let num = ReusableRc::try_unwrap(rc).unwrap();
+    ///   assert_eq!(*num, 42);
+    /// ```
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn try_unwrap(this: Self) -> Result<Reusable<T, A>, Self> {
+        if !Self::is_unique(&this) {
+            return Err(this);
+        }
+        let this = ManuallyDrop::new(this);
+        unsafe {
+            let inner = this.ptr.as_ptr();
+            let growable = ManuallyDrop::take(&mut (*inner).growable);
+            drop(ptr::read(&(*inner).pool));
+            // NB: The header goes away, the value is moved to the beginning of the `Growable`.
+            let value = ptr::addr_of_mut!((*inner).value);
+            let start = growable.ptr.as_ptr();
+            ptr::copy(value.cast::<u8>(), start, mem::size_of_val(&*value));
+            let ptr = NonNull::new_unchecked(ptr::from_raw_parts_mut(start, ptr::metadata(value)));
+            Ok(growable.into_reusable(ptr))
+        }
+    }
+
+    /// Drops this reference, returning the memory back as a [`Growable`]
+    /// if it was the last one.
+    ///
+    /// # Notes
+    ///
+    /// The value is dropped along with the last strong reference, the memory is returned
+    /// only if there are no weak references left as well.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn into_growable(this: Self) -> Option<Growable<A>> {
+        let mut this = ManuallyDrop::new(this);
+        unsafe { this.release() }.map(|(growable, _)| growable)
+    }
+
+    fn is_unique(this: &Self) -> bool {
+        let inner = this.inner();
+        inner.strong.get() == 1 && inner.weak.get() == 1
+    }
+
+    fn inner(&self) -> &RcBox<T, A> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Drops this strong reference, returning the memory if it was the last reference.
+    unsafe fn release(&mut self) -> Option<(Growable<A>, PoolHook<A>)> {
+        let inner = self.inner();
+        inner.strong.set(inner.strong.get() - 1);
+        if inner.strong.get() != 0 {
+            return None;
+        }
+        ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr.as_ptr()).value));
+        // NB: The strong references hold a single weak reference all together.
+        release_weak(self.ptr)
+    }
+}
+
+impl<T, A> Clone for WeakReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        let inner = unsafe { self.ptr.as_ref() };
+        increment(&inner.weak);
+        WeakReusableRc {
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<T, A> fmt::Debug for WeakReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "(Weak)")
+    }
+}
+
+impl<T, A> Drop for WeakReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some((growable, pool)) = unsafe { release_weak(self.ptr) } {
+            free_to(pool, growable);
+        }
+    }
+}
+
+impl<T, U, A> CoerceUnsized<WeakReusableRc<U, A>> for WeakReusableRc<T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<T, A> WeakReusableRc<T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Returns a strong reference to the value if it is still alive.
+    pub fn upgrade(&self) -> Option<ReusableRc<T, A>> {
+        let inner = unsafe { self.ptr.as_ref() };
+        if inner.strong.get() == 0 {
+            return None;
+        }
+        increment(&inner.strong);
+        Some(ReusableRc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of strong references to the value.
+    pub fn strong_count(&self) -> usize {
+        unsafe { self.ptr.as_ref() }.strong.get()
+    }
+}
+
+impl<A> Growable<A>
+where
+    A: Allocator + Clone,
+{
+    /// Places the value into a [`ReusableRc`], reusing the memory of this `Growable`.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let rc = Growable::new().consume_rc(42u32);
+    ///   assert_eq!(*rc, 42);
+    /// ```
+    ///
+    /// [`ReusableRc`]: struct.ReusableRc.html
+    #[inline]
+    pub fn consume_rc<T>(self, t: T) -> ReusableRc<T, A> {
+        ReusableRc::new_in(self, t, None)
+    }
+}
+
+/// Increments the reference count, aborting if it goes above [`MAX_REFCOUNT`].
+#[inline]
+fn increment(count: &Cell<usize>) {
+    let new = count.get() + 1;
+    if new > MAX_REFCOUNT {
+        process::abort();
+    }
+    count.set(new);
+}
+
+/// Drops a weak reference, returning the memory if it was the last reference.
+unsafe fn release_weak<T, A>(ptr: NonNull<RcBox<T, A>>) -> Option<(Growable<A>, PoolHook<A>)>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    let inner = ptr.as_ptr();
+    (*inner).weak.set((*inner).weak.get() - 1);
+    if (*inner).weak.get() != 0 {
+        return None;
+    }
+    Some((ManuallyDrop::take(&mut (*inner).growable), ptr::read(&(*inner).pool)))
+}
@@ -0,0 +1,256 @@
+use crate::{Growable, GrowablePool, Reusable};
+use std::{
+    alloc::{Allocator, Global, Layout},
+    cell::RefCell,
+    fmt,
+    marker::Unsize,
+    mem::{self, ManuallyDrop},
+    ops::{self, CoerceUnsized},
+    ptr::NonNull,
+};
+
+/// A scope handing out [`ScopedReusable`] values, see [`GrowablePool::scope`].
+///
+/// [`GrowablePool::scope`]: struct.GrowablePool.html#method.scope
+/// [`ScopedReusable`]: struct.ScopedReusable.html
+pub struct GrowableScope<'scope, A: Allocator + Clone = Global> {
+    pool: RefCell<&'scope mut GrowablePool<A>>,
+    lent: RefCell<Vec<Option<Growable<A>>>>,
+    vacant: RefCell<Vec<usize>>,
+}
+
+impl<'scope, A> fmt::Debug for GrowableScope<'scope, A>
+where
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let lent = self.lent.borrow().len() - self.vacant.borrow().len();
+        write!(formatter, "GrowableScope {{ .. {} allocations outstanding .. }}", lent)
+    }
+}
+
+impl<'scope, A> GrowableScope<'scope, A>
+where
+    A: Allocator + Clone,
+{
+    /// Allocates a new [`ScopedReusable`] from the pool.
+    ///
+    /// [`ScopedReusable`]: struct.ScopedReusable.html
+    #[inline]
+    pub fn allocate<T>(&'scope self, t: T) -> ScopedReusable<'scope, T, A> {
+        let reusable = self.pool.borrow_mut().allocate(t);
+        self.lend(reusable)
+    }
+
+    /// Allocates a new [`ScopedReusable`] from the pool, storing the value returned
+    /// by the given closure.
+    ///
+    /// [`ScopedReusable`]: struct.ScopedReusable.html
+    #[inline]
+    pub fn allocate_with<T, F>(&'scope self, f: F) -> ScopedReusable<'scope, T, A>
+    where
+        F: FnOnce() -> T,
+    {
+        // NB: The closure runs outside of the borrow, it might want to allocate as well.
+        let growable = self.pool.borrow_mut().take(mem::size_of::<T>(), mem::align_of::<T>());
+        self.lend(growable.consume_with(f))
+    }
+
+    /// Allocates a new [`ScopedReusable`] from the pool, cloning the slice into it.
+    ///
+    /// [`ScopedReusable`]: struct.ScopedReusable.html
+    #[inline]
+    pub fn allocate_slice_clone<T>(&'scope self, slice: &[T]) -> ScopedReusable<'scope, [T], A>
+    where
+        T: Clone,
+    {
+        let layout = Layout::for_value(slice);
+        let growable = self.pool.borrow_mut().take(layout.size(), layout.align());
+        self.lend(growable.consume_slice_clone(slice))
+    }
+
+    /// Allocates a new [`ScopedReusable`] from the pool, copying the string into it.
+    ///
+    /// [`ScopedReusable`]: struct.ScopedReusable.html
+    #[inline]
+    pub fn allocate_str(&'scope self, s: &str) -> ScopedReusable<'scope, str, A> {
+        let reusable = self.pool.borrow_mut().allocate_str(s);
+        self.lend(reusable)
+    }
+
+    fn lend<T>(&'scope self, reusable: Reusable<T, A>) -> ScopedReusable<'scope, T, A>
+    where
+        T: ?Sized,
+    {
+        let (ptr, growable) = Reusable::into_raw_parts(reusable);
+        let mut lent = self.lent.borrow_mut();
+        let slot = match self.vacant.borrow_mut().pop() {
+            Some(slot) => {
+                lent[slot] = Some(growable);
+                slot
+            },
+            None => {
+                lent.push(Some(growable));
+                lent.len() - 1
+            },
+        };
+        ScopedReusable {
+            ptr,
+            slot,
+            scope: self,
+        }
+    }
+
+    fn reclaim(&self, slot: usize) -> Growable<A> {
+        self.vacant.borrow_mut().push(slot);
+        self.lent.borrow_mut()[slot].take().expect("the slot is lent")
+    }
+}
+
+/// Returns every block still lent by the scope back to the pool, the values are leaked.
+struct Reclaim<'a, 'scope, A: Allocator + Clone>(&'a GrowableScope<'scope, A>);
+
+impl<'a, 'scope, A> Drop for Reclaim<'a, 'scope, A>
+where
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        let lent = mem::take(&mut *self.0.lent.borrow_mut());
+        let mut pool = self.0.pool.borrow_mut();
+        for growable in lent.into_iter().flatten() {
+            pool.free(growable);
+        }
+    }
+}
+
+/// A [`Reusable`] bound to a [`GrowableScope`] that goes back to the pool on drop,
+/// or at the end of the scope at the latest.
+///
+/// [`GrowableScope`]: struct.GrowableScope.html
+/// [`Reusable`]: struct.Reusable.html
+pub struct ScopedReusable<'scope, T: ?Sized, A: Allocator + Clone = Global> {
+    ptr: NonNull<T>,
+    slot: usize,
+    scope: &'scope GrowableScope<'scope, A>,
+}
+
+impl<'scope, T, A> ops::Deref for ScopedReusable<'scope, T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<'scope, T, A> ops::DerefMut for ScopedReusable<'scope, T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<'scope, T, A> fmt::Pointer for ScopedReusable<'scope, T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr, formatter)
+    }
+}
+
+impl<'scope, T, A> fmt::Debug for ScopedReusable<'scope, T, A>
+where
+    T: fmt::Debug + ?Sized,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<'scope, T, A> Drop for ScopedReusable<'scope, T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        let reusable = unsafe { self.scope.reclaim(self.slot).into_reusable(self.ptr) };
+        // NB: The value is dropped first, its destructor might want the scope as well.
+        let growable = Reusable::free(reusable);
+        self.scope.pool.borrow_mut().free(growable);
+    }
+}
+
+impl<'scope, T, U, A> CoerceUnsized<ScopedReusable<'scope, U, A>> for ScopedReusable<'scope, T, A>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+    A: Allocator + Clone,
+{
+}
+
+impl<'scope, T, A> ScopedReusable<'scope, T, A>
+where
+    T: ?Sized,
+    A: Allocator + Clone,
+{
+    /// Detaches this handle from the scope, returning a plain [`Reusable`].
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    pub fn into_reusable(this: Self) -> Reusable<T, A> {
+        let this = ManuallyDrop::new(this);
+        unsafe { this.scope.reclaim(this.slot).into_reusable(this.ptr) }
+    }
+}
+
+impl<A> GrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    /// Runs the closure with a [`GrowableScope`] allocating from this pool.
+    ///
+    /// # Notes
+    ///
+    /// Every [`ScopedReusable`] goes back to the pool on drop, the ones still outstanding
+    /// at the end of the closure, e.g. leaked through [`mem::forget`], go back without
+    /// their values being dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut pool = GrowablePool::builder().with_capacity(2).build();
+    ///   let sum = pool.scope(|scope| {
+    ///       let a = scope.allocate(1u32);
+    ///       let b = scope.allocate(2u32);
+    ///       std::mem::forget(b);
+    ///       *a + 2
+    ///   });
+    ///   assert_eq!(sum, 3);
+    ///   assert_eq!(pool.len(), 2);
+    /// ```
+    ///
+    /// [`GrowableScope`]: struct.GrowableScope.html
+    /// [`ScopedReusable`]: struct.ScopedReusable.html
+    /// [`mem::forget`]: https://doc.rust-lang.org/std/mem/fn.forget.html
+    pub fn scope<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'scope> FnOnce(&'scope GrowableScope<'scope, A>) -> R,
+    {
+        let scope = GrowableScope {
+            pool: RefCell::new(self),
+            lent: RefCell::new(Vec::new()),
+            vacant: RefCell::new(Vec::new()),
+        };
+        let _reclaim = Reclaim(&scope);
+        f(&scope)
+    }
+}
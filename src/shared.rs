@@ -0,0 +1,644 @@
+#[cfg(feature = "stats")]
+use crate::PoolStats;
+use crate::{
+    arc::ArcBox,
+    rc::RcBox,
+    stats::Regrowths,
+    sync::{Mutex, MutexGuard},
+    Growable, GrowableCache, GrowablePool, GrowablePoolBuilder, Pooled, Reusable, ReusableArc,
+    ReusableRc, ReusableString, ReusableVec,
+};
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    collections::HashMap,
+    fmt,
+    mem::{self, MaybeUninit},
+    ops, process,
+    ptr::{self, NonNull},
+    sync::{Arc, PoisonError, Weak},
+};
+
+/// The amount of objects each [`GrowableCache`] keeps per magazine unless configured otherwise.
+///
+/// [`GrowableCache`]: struct.GrowableCache.html
+pub(crate) const DEFAULT_MAGAZINE_LEN: usize = 16;
+
+/// A thread-safe [`GrowablePool`] that allocates and frees objects through a shared reference.
+///
+/// # Notes
+///
+/// The pool is guarded by a mutex which is only held while a [`Growable`] is taken from
+/// or returned to the pool, a value itself is moved in (and a [`Growable`] is regrown if needed)
+/// after the lock is released. The overgrow and capacity options are exactly the same
+/// as for the [`GrowablePool`].
+///
+/// Use a [`GrowableCache`] per thread to avoid contention on the lock entirely.
+///
+/// A reference to the pool is an [`Allocator`], so it can be used with standard collections
+/// as well.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::{sync::Arc, thread};
+///   let pool = Arc::new(GrowablePool::builder().with_capacity(4).build_shared());
+///   let handles: Vec<_> = (0 .. 4)
+///       .map(|i| {
+///           let pool = Arc::clone(&pool);
+///           thread::spawn(move || pool.free(pool.allocate(i)))
+///       })
+///       .collect();
+///   for handle in handles {
+///       handle.join().unwrap();
+///   }
+///   assert_eq!(pool.len(), 4);
+/// ```
+///
+/// [`Allocator`]: https://doc.rust-lang.org/std/alloc/trait.Allocator.html
+/// [`Growable`]: struct.Growable.html
+/// [`GrowableCache`]: struct.GrowableCache.html
+/// [`GrowablePool`]: struct.GrowablePool.html
+pub struct SyncGrowablePool<A: Allocator = Global> {
+    pool: Mutex<Shared<A>>,
+    magazine_len: usize,
+}
+
+/// The pool along with the blocks lent through the [`Allocator`] API, guarded by a single lock.
+///
+/// [`Allocator`]: https://doc.rust-lang.org/std/alloc/trait.Allocator.html
+pub(crate) struct Shared<A: Allocator> {
+    pool: GrowablePool<A>,
+    // NB: Blocks lent through the Allocator API along with their actual layout,
+    // since a caller only knows about the layout it asked for.
+    lent: HashMap<usize, Layout>,
+}
+
+impl<A> ops::Deref for Shared<A>
+where
+    A: Allocator,
+{
+    type Target = GrowablePool<A>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pool
+    }
+}
+
+impl<A> ops::DerefMut for Shared<A>
+where
+    A: Allocator,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.pool
+    }
+}
+
+impl<A> Clone for SyncGrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        SyncGrowablePool::with_magazine_capacity(self.lock().pool.clone(), self.magazine_len)
+    }
+}
+
+impl<A> fmt::Debug for SyncGrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "SyncGrowablePool {{ .. {} more allocations available .. }}", self.len())
+    }
+}
+
+impl Default for SyncGrowablePool {
+    fn default() -> Self {
+        SyncGrowablePool::new()
+    }
+}
+
+impl<A> From<GrowablePool<A>> for SyncGrowablePool<A>
+where
+    A: Allocator,
+{
+    fn from(pool: GrowablePool<A>) -> Self {
+        SyncGrowablePool::with_magazine_capacity(pool, DEFAULT_MAGAZINE_LEN)
+    }
+}
+
+impl<A> SyncGrowablePool<A>
+where
+    A: Allocator,
+{
+    /// Wraps the pool, each [`GrowableCache`] keeps up to `magazine_len` objects per magazine.
+    ///
+    /// [`GrowableCache`]: struct.GrowableCache.html
+    pub(crate) fn with_magazine_capacity(pool: GrowablePool<A>, magazine_len: usize) -> Self {
+        SyncGrowablePool {
+            pool: Mutex::new(Shared {
+                pool,
+                lent: HashMap::new(),
+            }),
+            magazine_len,
+        }
+    }
+
+    /// Returns the amount of objects each [`GrowableCache`] keeps per magazine.
+    ///
+    /// [`GrowableCache`]: struct.GrowableCache.html
+    #[inline]
+    pub(crate) fn magazine_len(&self) -> usize {
+        self.magazine_len
+    }
+
+    /// Locks the pool, a poisoned lock is not a problem since the pool
+    /// is never left in an inconsistent state.
+    #[inline]
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Shared<A>> {
+        self.pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Every allocation takes a [`Growable`] from the pool (regrowing it if needed) and
+/// every deallocation returns it back.
+///
+/// # Notes
+///
+/// Same as for the other methods, the lock is not held while a [`Growable`] is regrown.
+/// An allocator failure is reported as an [`AllocError`], it never aborts.
+///
+/// # Safety
+///
+/// A block passed to `deallocate`, `grow` or `shrink` must be currently allocated by this very
+/// pool, the process is aborted otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # use growable::*;
+///   let pool = GrowablePool::builder().with_capacity(1).build_shared();
+///   let mut vec = Vec::new_in(&pool);
+///   vec.extend([1u32, 2, 3, 4]);
+///   drop(vec);
+///   let b = Box::new_in([0u8; 16], &pool);
+///   assert_eq!(pool.len(), 0);
+///   drop(b);
+///   assert_eq!(pool.len(), 1);
+/// ```
+///
+/// [`AllocError`]: https://doc.rust-lang.org/std/alloc/struct.AllocError.html
+/// [`Growable`]: struct.Growable.html
+unsafe impl<A> Allocator for &SyncGrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(dangling(layout));
+        }
+        let (mut growable, _) = self.lock().try_take(layout.size(), layout.align())?;
+        if growable.grow(layout.size(), layout.align(), true).is_err() {
+            self.free(growable);
+            return Err(AllocError);
+        }
+        let regrowths = mem::take(&mut growable.regrowths);
+        let (ptr, block, _) = growable.into_raw_parts();
+        let mut shared = self.lock();
+        shared.counters.regrow(regrowths);
+        shared.lent.insert(ptr.as_ptr().addr(), block);
+        Ok(NonNull::slice_from_raw_parts(ptr, block.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        let mut shared = self.lock();
+        let block = match shared.lent.remove(&ptr.as_ptr().addr()) {
+            Some(block) => block,
+            None => {
+                // NB: Deallocation must not unwind, the safety contract is broken anyway.
+                debug_assert!(
+                    false,
+                    "SyncGrowablePool::deallocate: the block is not allocated by this pool"
+                );
+                process::abort();
+            },
+        };
+        let growable = Growable {
+            len: block.size(),
+            ptr_alignment: block.align(),
+            ptr,
+            growth_policy: shared.growth_policy,
+            regrowths: Regrowths::default(),
+            alloc: shared.alloc.clone(),
+        };
+        shared.free(growable);
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if let Some(block) = self.lent_block(ptr, old_layout, new_layout) {
+            // The block is large enough already.
+            return Ok(NonNull::slice_from_raw_parts(ptr, block.size()));
+        }
+        let new_ptr = Allocator::allocate(self, new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_mut_ptr(), old_layout.size());
+        Allocator::deallocate(self, ptr, old_layout);
+        Ok(new_ptr)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if new_layout.size() == 0 {
+            // A zero-sized allocation never owns a block, this one goes back to the pool.
+            Allocator::deallocate(self, ptr, old_layout);
+            return Ok(dangling(new_layout));
+        }
+        if let Some(block) = self.lent_block(ptr, old_layout, new_layout) {
+            return Ok(NonNull::slice_from_raw_parts(ptr, block.size()));
+        }
+        let new_ptr = Allocator::allocate(self, new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_mut_ptr(), new_layout.size());
+        Allocator::deallocate(self, ptr, old_layout);
+        Ok(new_ptr)
+    }
+}
+
+/// Returns a dangling pointer for a zero-sized allocation.
+#[inline]
+fn dangling(layout: Layout) -> NonNull<[u8]> {
+    let ptr = unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) };
+    NonNull::slice_from_raw_parts(ptr, 0)
+}
+
+impl SyncGrowablePool {
+    /// Creates a new pool with default options.
+    ///
+    /// # Notes
+    ///
+    /// See [`GrowablePoolBuilder`] for advanced configuration.
+    ///
+    /// [`GrowablePoolBuilder`]: struct.GrowablePoolBuilder.html
+    pub fn new() -> Self {
+        GrowablePoolBuilder::default().build_shared()
+    }
+}
+
+impl<A> SyncGrowablePool<A>
+where
+    A: Allocator + Clone,
+{
+    /// Creates a new pool with default options and the given allocator.
+    ///
+    /// # Notes
+    ///
+    /// See [`GrowablePoolBuilder`] for advanced configuration.
+    ///
+    /// [`GrowablePoolBuilder`]: struct.GrowablePoolBuilder.html
+    pub fn new_in(alloc: A) -> Self {
+        GrowablePoolBuilder::new_in(alloc).build_shared()
+    }
+
+    /// Creates a new thread-local [`GrowableCache`] on top of this pool.
+    ///
+    /// [`GrowableCache`]: struct.GrowableCache.html
+    pub fn cache(&self) -> GrowableCache<'_, A> {
+        GrowableCache::new(self)
+    }
+
+    /// Consumes this pool, returning the underlying [`GrowablePool`].
+    ///
+    /// [`GrowablePool`]: struct.GrowablePool.html
+    pub fn into_inner(self) -> GrowablePool<A> {
+        self.pool.into_inner().unwrap_or_else(PoisonError::into_inner).pool
+    }
+
+    /// Returns true if a reallocation will be needed to allocate an another one object.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the current amount of allocations that this pool can provide without a reallocation.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns the total capacity in bytes of all [`Growable`] objects held by the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.lock().bytes()
+    }
+
+    /// Returns a snapshot of the pool statistics.
+    ///
+    /// # Notes
+    ///
+    /// Only available with the `stats` feature enabled.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> PoolStats {
+        self.lock().stats()
+    }
+
+    /// Resets the pool statistics.
+    ///
+    /// # Notes
+    ///
+    /// Only available with the `stats` feature enabled.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&self) {
+        self.lock().reset_stats();
+    }
+
+    /// Allocates a new [`Reusable`] from the pool.
+    ///
+    /// # Notes
+    ///
+    /// See [`GrowablePool::allocate`] for details.
+    ///
+    /// [`GrowablePool::allocate`]: struct.GrowablePool.html#method.allocate
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate<T>(&self, t: T) -> Reusable<T, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume(t)
+    }
+
+    /// Allocates a new [`Pooled`] from the pool, it goes back to this pool once dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::sync::Arc;
+    ///   let pool = Arc::new(SyncGrowablePool::new());
+    ///   let name = pool.allocate_pooled(String::from("Foo"));
+    ///   assert_eq!(*name, "Foo");
+    /// ```
+    ///
+    /// [`Pooled`]: struct.Pooled.html
+    #[inline]
+    pub fn allocate_pooled<T>(self: &Arc<Self>, t: T) -> Pooled<T, A> {
+        Pooled::from_reusable(self.allocate(t), self)
+    }
+
+    /// Allocates a new [`ReusableRc`] from the pool, its memory goes back to this pool
+    /// once the last reference is dropped.
+    ///
+    /// [`ReusableRc`]: struct.ReusableRc.html
+    #[inline]
+    pub fn allocate_rc<T>(self: &Arc<Self>, t: T) -> ReusableRc<T, A> {
+        let growable = self.take(mem::size_of::<RcBox<T, A>>(), mem::align_of::<RcBox<T, A>>());
+        ReusableRc::new_in(growable, t, Some(Arc::downgrade(self)))
+    }
+
+    /// Allocates a new [`ReusableArc`] from the pool, its memory goes back to this pool
+    /// once the last reference is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    /// # use std::sync::Arc;
+    ///   let pool = Arc::new(SyncGrowablePool::new());
+    ///   let arc = pool.allocate_arc(42u32);
+    ///   let weak = ReusableArc::downgrade(&arc);
+    ///   drop(arc);
+    ///   assert_eq!(pool.len(), 0);
+    ///   drop(weak);
+    ///   assert_eq!(pool.len(), 1);
+    /// ```
+    ///
+    /// [`ReusableArc`]: struct.ReusableArc.html
+    #[inline]
+    pub fn allocate_arc<T>(self: &Arc<Self>, t: T) -> ReusableArc<T, A> {
+        let growable = self.take(mem::size_of::<ArcBox<T, A>>(), mem::align_of::<ArcBox<T, A>>());
+        ReusableArc::new_in(growable, t, Some(Arc::downgrade(self)))
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, storing the value returned
+    /// by the given closure.
+    ///
+    /// # Notes
+    ///
+    /// The closure is called with the pool unlocked.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_with<T, F>(&self, f: F) -> Reusable<T, A>
+    where
+        F: FnOnce() -> T,
+    {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_with(f)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool with an uninitialized value.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_uninit<T>(&self) -> Reusable<MaybeUninit<T>, A> {
+        self.take(mem::size_of::<T>(), mem::align_of::<T>()).consume_uninit()
+    }
+
+    /// Tries to allocate a new [`Reusable`] from the pool.
+    ///
+    /// # Errors
+    ///
+    /// See [`GrowablePool::try_allocate`] for details.
+    ///
+    /// [`GrowablePool::try_allocate`]: struct.GrowablePool.html#method.try_allocate
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn try_allocate<T>(&self, t: T) -> Result<Reusable<T, A>, T> {
+        self.lock().try_allocate(t)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool but only if there is a [`Growable`] already
+    /// capable to store the value, never touching the allocator.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back if no suitable [`Growable`] is available in the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_no_grow<T>(&self, t: T) -> Result<Reusable<T, A>, T> {
+        self.lock().allocate_no_grow(t)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, moving an unsized value out of the [`Box`].
+    ///
+    /// [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_box<T, B>(&self, b: Box<T, B>) -> Reusable<T, A>
+    where
+        T: ?Sized,
+        B: Allocator,
+    {
+        let layout = Layout::for_value::<T>(&b);
+        self.take(layout.size(), layout.align()).consume_box(b)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, cloning the slice into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_clone<T>(&self, slice: &[T]) -> Reusable<[T], A>
+    where
+        T: Clone,
+    {
+        let layout = Layout::for_value(slice);
+        self.take(layout.size(), layout.align()).consume_slice_clone(slice)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, collecting the iterator into a slice.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_slice_from_iter<T, I>(&self, iter: I) -> Reusable<[T], A>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len().saturating_mul(mem::size_of::<T>());
+        self.take(len, mem::align_of::<T>()).consume_slice_from_iter(iter)
+    }
+
+    /// Allocates a new [`Reusable`] from the pool, copying the string into it.
+    ///
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn allocate_str(&self, s: &str) -> Reusable<str, A> {
+        self.take(s.len(), 1).consume_str(s)
+    }
+
+    /// Allocates a new empty [`ReusableVec`] from the pool.
+    ///
+    /// [`ReusableVec`]: struct.ReusableVec.html
+    #[inline]
+    pub fn allocate_vec<T>(&self) -> ReusableVec<T, A> {
+        ReusableVec::from_growable(self.take(0, 1))
+    }
+
+    /// Allocates a new empty [`ReusableString`] from the pool.
+    ///
+    /// [`ReusableString`]: struct.ReusableString.html
+    #[inline]
+    pub fn allocate_string(&self) -> ReusableString<A> {
+        ReusableString::from_growable(self.take(0, 1))
+    }
+
+    /// Returns the [`Reusable`] back to the pool, marking it
+    /// available for a next allocation.
+    ///
+    /// # Notes
+    ///
+    /// See [`GrowablePool::free`] for details.
+    ///
+    /// [`GrowablePool::free`]: struct.GrowablePool.html#method.free
+    /// [`Reusable`]: struct.Reusable.html
+    #[inline]
+    pub fn free<G>(&self, t: G)
+    where
+        G: Into<Growable<A>>,
+    {
+        let growable = t.into();
+        self.lock().free(growable);
+    }
+
+    /// Drops the least recently returned [`Growable`] objects until
+    /// there are at most `len` of them left in the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn trim_to_len(&self, len: usize) {
+        self.lock().trim_to_len(len);
+    }
+
+    /// Drops the least recently returned [`Growable`] objects until
+    /// the pool holds at most `bytes` bytes.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn trim_to_bytes(&self, bytes: usize) {
+        self.lock().trim_to_bytes(bytes);
+    }
+
+    /// Drops the least recently returned [`Growable`] objects that overgrew the pool
+    /// capacity and releases any spare memory the pool itself holds.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn shrink_to_fit(&self) {
+        self.lock().shrink_to_fit();
+    }
+
+    /// Allocates default [`Growable`] objects until there are at least `len` of them
+    /// available in the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn reserve(&self, len: usize) {
+        self.lock().reserve(len);
+    }
+
+    /// Allocates [`Growable`] objects until there are at least `len` of them
+    /// capable to store a value of type `T` available in the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub fn reserve_for<T>(&self, len: usize) {
+        self.lock().reserve_for::<T>(len);
+    }
+
+    /// Returns the actual layout of a lent block if it is capable to store the new layout.
+    fn lent_block(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Option<Layout> {
+        if old_layout.size() == 0 {
+            return None;
+        }
+        let block = *self.lock().lent.get(&ptr.as_ptr().addr())?;
+        (block.size() >= new_layout.size() && block.align() >= new_layout.align()).then_some(block)
+    }
+
+    /// Takes a [`Growable`] from the pool, holding the lock for this call only.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    fn take(&self, len: usize, ptr_alignment: usize) -> Growable<A> {
+        self.lock().take(len, ptr_alignment)
+    }
+}
+
+/// The pool a reference-counted [`Growable`] goes back to once released, if any.
+///
+/// [`Growable`]: struct.Growable.html
+pub(crate) type PoolHook<A> = Option<Weak<SyncGrowablePool<A>>>;
+
+/// Frees the [`Growable`] to the pool if it is still alive, deallocates it otherwise.
+///
+/// [`Growable`]: struct.Growable.html
+pub(crate) fn free_to<A>(pool: PoolHook<A>, growable: Growable<A>)
+where
+    A: Allocator + Clone,
+{
+    match pool.and_then(|pool| pool.upgrade()) {
+        Some(pool) => pool.free(growable),
+        None => drop(growable),
+    }
+}
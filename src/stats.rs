@@ -0,0 +1,196 @@
+use crate::Growable;
+use std::alloc::Allocator;
+
+/// A snapshot of [`GrowablePool`] statistics.
+///
+/// # Notes
+///
+/// Only available with the `stats` feature enabled.
+///
+/// Byte counters rely on the capacity of each [`Growable`] at the moment it leaves or enters
+/// the pool, so they are approximate if a block is resized in between (e.g. by a [`ReusableVec`])
+/// or if a foreign block is returned to the pool.
+///
+/// A [`Growable`] keeps track of its own reallocations while it is out of the pool, they are
+/// recorded once it is returned (right away for a block lent through the allocator API).
+/// Objects moved between a [`GrowableCache`] and the pool are counted as allocations and frees
+/// as well, each one outstanding with its whole capacity while it sits in the cache. A
+/// [`GrowablePool::allocate_no_grow`] call that finds nothing suitable is not counted at all.
+///
+/// [`Growable`]: struct.Growable.html
+/// [`GrowableCache`]: struct.GrowableCache.html
+/// [`GrowablePool`]: struct.GrowablePool.html
+/// [`GrowablePool::allocate_no_grow`]: struct.GrowablePool.html#method.allocate_no_grow
+/// [`ReusableVec`]: struct.ReusableVec.html
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PoolStats {
+    /// The amount of allocation requests.
+    pub allocations: usize,
+    /// The amount of objects returned back to the pool.
+    pub frees: usize,
+    /// The amount of allocations served without a pool reallocation.
+    pub hits: usize,
+    /// The amount of pool reallocations.
+    pub refills: usize,
+    /// The amount of times a [`Growable`] taken from the pool was reallocated to store
+    /// a larger value.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    pub regrowths: usize,
+    /// The amount of objects dropped because of the pool capacity or memory budget.
+    pub dropped: usize,
+    /// The total amount of bytes currently held by the pool.
+    pub bytes_held: usize,
+    /// The total amount of bytes currently taken from the pool.
+    pub bytes_outstanding: usize,
+    /// The highest amount of bytes taken from the pool at once.
+    pub peak_bytes_outstanding: usize,
+}
+
+/// Pool counters, zero-sized unless the `stats` feature is enabled.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Counters {
+    #[cfg(feature = "stats")]
+    stats: PoolStats,
+}
+
+#[cfg_attr(not(feature = "stats"), allow(unused_variables))]
+impl Counters {
+    /// Records an allocation request.
+    #[inline]
+    pub(crate) fn allocation(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.allocations += 1;
+        }
+    }
+
+    /// Records an allocation served without a pool reallocation.
+    #[inline]
+    pub(crate) fn hit(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.hits += 1;
+        }
+    }
+
+    /// Records a pool reallocation.
+    #[inline]
+    pub(crate) fn refill(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.refills += 1;
+        }
+    }
+
+    /// Records a [`Growable`] taken from the pool to store `len` bytes with the given alignment.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub(crate) fn take<A>(&mut self, growable: &Growable<A>, len: usize, ptr_alignment: usize)
+    where
+        A: Allocator,
+    {
+        #[cfg(feature = "stats")]
+        {
+            let len = match growable.fits(len, ptr_alignment) {
+                true => growable.len,
+                false => std::cmp::max(growable.len, growable.growth_policy.round(len)),
+            };
+            self.stats.bytes_outstanding += len;
+            self.stats.peak_bytes_outstanding =
+                std::cmp::max(self.stats.peak_bytes_outstanding, self.stats.bytes_outstanding);
+        }
+    }
+
+    /// Records the reallocations a [`Growable`] went through since it was taken from the pool.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub(crate) fn regrow(&mut self, regrowths: Regrowths) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.regrowths += regrowths.count;
+        }
+    }
+
+    /// Records a [`Growable`] of `len` bytes put back to the pool after a failed allocation.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub(crate) fn put_back(&mut self, len: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.bytes_outstanding = self.stats.bytes_outstanding.saturating_sub(len);
+        }
+    }
+
+    /// Records a [`Growable`] of `len` bytes returned to the pool, possibly dropped.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub(crate) fn free(&mut self, len: usize, dropped: bool) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.frees += 1;
+            self.stats.dropped += dropped as usize;
+            self.stats.bytes_outstanding = self.stats.bytes_outstanding.saturating_sub(len);
+        }
+    }
+
+    /// Records `len` [`Growable`] objects held by the pool dropped to make room
+    /// for a returning one.
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub(crate) fn evict(&mut self, len: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.dropped += len;
+        }
+    }
+
+    /// Returns a snapshot of the counters.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub(crate) fn get(&self, bytes_held: usize) -> PoolStats {
+        PoolStats {
+            bytes_held,
+            ..self.stats
+        }
+    }
+
+    /// Resets the counters, keeping track of the bytes currently taken from the pool.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub(crate) fn reset(&mut self) {
+        let bytes_outstanding = self.stats.bytes_outstanding;
+        self.stats = PoolStats {
+            bytes_outstanding,
+            peak_bytes_outstanding: bytes_outstanding,
+            ..PoolStats::default()
+        };
+    }
+}
+
+/// Reallocations of a single [`Growable`] not yet recorded by a pool,
+/// zero-sized unless the `stats` feature is enabled.
+///
+/// [`Growable`]: struct.Growable.html
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Regrowths {
+    #[cfg(feature = "stats")]
+    count: usize,
+}
+
+impl Regrowths {
+    /// Records a reallocation.
+    #[inline]
+    pub(crate) fn record(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.count += 1;
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{Growable, ReusableVec};
+use std::{
+    alloc::{Allocator, Global},
+    fmt, ops, str,
+};
+
+/// A growable UTF-8 string backed by a [`Growable`]. It behaves just like the default
+/// [`String`] but its memory can be fetched back as a [`Growable`] when it is no longer needed.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+/// # use std::fmt::Write;
+///   let mut pool = GrowablePool::default();
+///   let mut s = pool.allocate_string();
+///   s.push_str("Hello");
+///   write!(s, " {}!", "World").unwrap();
+///   assert_eq!(&*s, "Hello World!");
+///   // The memory goes back to the pool and might be used to store something else.
+///   pool.free(s);
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+pub struct ReusableString<A: Allocator = Global> {
+    vec: ReusableVec<u8, A>,
+}
+
+impl<A> Clone for ReusableString<A>
+where
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        ReusableString {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<A> fmt::Debug for ReusableString<A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<A> fmt::Display for ReusableString<A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, formatter)
+    }
+}
+
+impl<A> fmt::Write for ReusableString<A>
+where
+    A: Allocator,
+{
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<A> Default for ReusableString<A>
+where
+    A: Allocator + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A> ops::Deref for ReusableString<A>
+where
+    A: Allocator,
+{
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+}
+
+impl<A> ops::DerefMut for ReusableString<A>
+where
+    A: Allocator,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+}
+
+impl<A> From<ReusableString<A>> for Growable<A>
+where
+    A: Allocator,
+{
+    /// Returns the memory back, see [`ReusableString::free`].
+    ///
+    /// [`ReusableString::free`]: struct.ReusableString.html#method.free
+    #[inline]
+    fn from(s: ReusableString<A>) -> Self {
+        ReusableString::free(s)
+    }
+}
+
+impl ReusableString {
+    /// Returns a new empty `ReusableString`, no memory is allocated yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Returns a new empty `ReusableString` with memory already allocated to store
+    /// at least `capacity` bytes.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<A> ReusableString<A>
+where
+    A: Allocator,
+{
+    /// Returns a new empty `ReusableString` in the provided allocator, no memory is allocated yet.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Self::from_growable(Growable::new_in(alloc))
+    }
+
+    /// Returns a new empty `ReusableString` with memory already allocated in the provided
+    /// allocator to store at least `capacity` bytes.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        ReusableString {
+            vec: ReusableVec::with_capacity_in(capacity, alloc),
+        }
+    }
+
+    /// Returns a new empty `ReusableString` that uses the memory of the [`Growable`].
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn from_growable(buf: Growable<A>) -> Self {
+        ReusableString {
+            vec: ReusableVec::from_growable(buf),
+        }
+    }
+
+    /// Returns the memory back as a [`Growable`].
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn free(this: Self) -> Growable<A> {
+        ReusableVec::free(this.vec)
+    }
+
+    /// Returns the length in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if the string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of bytes that can be stored without a reallocation.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+
+    /// Extracts a string slice containing the entire string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Extracts a mutable string slice containing the entire string.
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+
+    /// Appends the string slice to the end.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.vec.extend_from_slice(s.as_bytes());
+    }
+
+    /// Appends the character to the end.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Shortens the string to the given length in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` does not lie on a char boundary.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            assert!(self.is_char_boundary(len), "ReusableString::truncate: not a char boundary");
+            self.vec.truncate(len);
+        }
+    }
+
+    /// Truncates the string to zero length, keeping the memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+}
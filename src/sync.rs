@@ -0,0 +1,16 @@
+//! Synchronization primitives used by the shared pool and [`ReusableArc`], replaced with
+//! the ones of [`loom`] when the crate is model checked with `RUSTFLAGS="--cfg loom"`.
+//!
+//! [`ReusableArc`]: ../struct.ReusableArc.html
+//! [`loom`]: https://docs.rs/loom
+
+#[cfg(loom)]
+pub(crate) use loom::{
+    hint,
+    sync::{atomic, Mutex, MutexGuard},
+};
+#[cfg(not(loom))]
+pub(crate) use std::{
+    hint,
+    sync::{atomic, Mutex, MutexGuard},
+};
@@ -0,0 +1,383 @@
+use crate::Growable;
+use std::{
+    alloc::{handle_alloc_error, Allocator, Global, Layout},
+    cmp, fmt,
+    marker::PhantomData,
+    mem, ops,
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// A contiguous growable array type backed by a [`Growable`]. It behaves just like the default
+/// [`Vec`] but its memory can be fetched back as a [`Growable`] when it is no longer needed.
+///
+/// # Examples
+///
+/// ```
+/// # use growable::*;
+///   let mut pool = GrowablePool::default();
+///   let mut vec = pool.allocate_vec::<u32>();
+///   vec.extend([1, 2, 3]);
+///   vec.push(4);
+///   assert_eq!(&*vec, &[1, 2, 3, 4]);
+///   // The memory goes back to the pool and might be used to store something else.
+///   pool.free(vec);
+///   let name = pool.allocate("Foo");
+///   assert_eq!(*name, "Foo");
+/// ```
+///
+/// [`Growable`]: struct.Growable.html
+/// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+pub struct ReusableVec<T, A: Allocator = Global> {
+    buf: Growable<A>,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T, A> Clone for ReusableVec<T, A>
+where
+    T: Clone,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut vec = ReusableVec::new_in(self.buf.alloc.clone());
+        vec.buf.set_growth_policy(self.buf.growth_policy());
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T, A> fmt::Debug for ReusableVec<T, A>
+where
+    T: fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+impl<T, A> Default for ReusableVec<T, A>
+where
+    A: Allocator + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T, A> ops::Deref for ReusableVec<T, A>
+where
+    A: Allocator,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, A> ops::DerefMut for ReusableVec<T, A>
+where
+    A: Allocator,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, A> Extend<T> for ReusableVec<T, A>
+where
+    A: Allocator,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for t in iter {
+            self.push(t);
+        }
+    }
+}
+
+impl<'a, T, A> Extend<&'a T> for ReusableVec<T, A>
+where
+    T: Copy + 'a,
+    A: Allocator,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, A> Drop for ReusableVec<T, A>
+where
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, A> From<ReusableVec<T, A>> for Growable<A>
+where
+    A: Allocator,
+{
+    /// Drops the items and returns the memory back, see [`ReusableVec::free`].
+    ///
+    /// [`ReusableVec::free`]: struct.ReusableVec.html#method.free
+    #[inline]
+    fn from(vec: ReusableVec<T, A>) -> Self {
+        ReusableVec::free(vec)
+    }
+}
+
+impl<T> ReusableVec<T> {
+    /// Returns a new empty `ReusableVec`, no memory is allocated yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Returns a new empty `ReusableVec` with memory already allocated to store
+    /// at least `capacity` items.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A> ReusableVec<T, A>
+where
+    A: Allocator,
+{
+    /// Returns a new empty `ReusableVec` in the provided allocator, no memory is allocated yet.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Self::from_growable(Growable::new_in(alloc))
+    }
+
+    /// Returns a new empty `ReusableVec` with memory already allocated in the provided allocator
+    /// to store at least `capacity` items.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut vec = Self::new_in(alloc);
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Returns a new empty `ReusableVec` that uses the memory of the [`Growable`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let vec = ReusableVec::<u32>::from_growable(Growable::with_capacity(16, 4));
+    ///   assert_eq!(vec.capacity(), 4);
+    /// ```
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn from_growable(buf: Growable<A>) -> Self {
+        ReusableVec {
+            buf,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Drops the items and returns the memory back as a [`Growable`].
+    ///
+    /// [`Growable`]: struct.Growable.html
+    #[inline]
+    pub fn free(mut this: Self) -> Growable<A> {
+        this.clear();
+        let this = mem::ManuallyDrop::new(this);
+        unsafe { ptr::read(&this.buf) }
+    }
+
+    /// Returns the number of items.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of items that can be stored without a reallocation.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else if self.buf.alignment() < mem::align_of::<T>() {
+            0
+        } else {
+            self.buf.len() / mem::size_of::<T>()
+        }
+    }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    /// Returns a raw pointer to the buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr().as_ptr()
+    }
+
+    /// Returns a raw mutable pointer to the buffer.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr().as_ptr()
+    }
+
+    /// Extracts a slice containing all the items.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    /// Extracts a mutable slice containing all the items.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Reserves capacity for at least `additional` more items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    pub fn reserve(&mut self, additional: usize) {
+        let capacity = self.capacity();
+        let required =
+            self.len.checked_add(additional).expect("ReusableVec::reserve: capacity overflow");
+        if required <= capacity {
+            return;
+        }
+        let capacity = cmp::max(cmp::max(capacity * 2, required), 4);
+        let layout = Layout::array::<T>(capacity).expect("ReusableVec::reserve: capacity overflow");
+        let len = self.buf.growth_policy().round(layout.size());
+        let ptr_alignment = cmp::max(self.buf.ptr_alignment, layout.align());
+        // Unlike a Growable the items must be preserved.
+        unsafe {
+            let ptr = Growable::allocate(&self.buf.alloc, len, ptr_alignment)
+                .unwrap_or_else(|layout| handle_alloc_error(layout));
+            ptr::copy_nonoverlapping(self.as_ptr(), ptr.cast::<T>().as_ptr(), self.len);
+            if self.buf.len != 0 {
+                self.buf.alloc.deallocate(
+                    self.buf.ptr,
+                    Layout::from_size_align_unchecked(self.buf.len, self.buf.ptr_alignment),
+                );
+            }
+            self.buf.ptr = ptr;
+            self.buf.len = len;
+            self.buf.ptr_alignment = ptr_alignment;
+            self.buf.regrowths.record();
+        }
+    }
+
+    /// Appends an item to the back.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    #[inline]
+    pub fn push(&mut self, t: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        unsafe { self.as_mut_ptr().add(self.len).write(t) };
+        self.len += 1;
+    }
+
+    /// Appends all the items of the slice to the back, reserving the capacity once.
+    ///
+    /// # Notes
+    ///
+    /// Might trigger `alloc_error` handler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use growable::*;
+    ///   let mut vec = ReusableVec::new();
+    ///   vec.extend_from_slice(&[1u32, 2]);
+    ///   vec.extend_from_slice(&[3, 4]);
+    ///   assert_eq!(&*vec, &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Copy,
+    {
+        self.reserve(slice.len());
+        unsafe {
+            let end = self.as_mut_ptr().add(self.len);
+            ptr::copy_nonoverlapping(slice.as_ptr(), end, slice.len());
+        }
+        self.len += slice.len();
+    }
+
+    /// Removes the last item and returns it.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe { Some(self.as_ptr().add(self.len).read()) }
+    }
+
+    /// Shortens the vector, keeping the first `len` items and dropping the rest.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail =
+            ptr::slice_from_raw_parts_mut(unsafe { self.as_mut_ptr().add(len) }, self.len - len);
+        // The length is updated first in case a drop panics.
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Drops all the items, keeping the memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    #[inline]
+    fn ptr(&self) -> NonNull<T> {
+        if mem::size_of::<T>() == 0 || self.buf.alignment() < mem::align_of::<T>() {
+            NonNull::dangling()
+        } else {
+            self.buf.ptr.cast()
+        }
+    }
+}
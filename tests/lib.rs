@@ -1,5 +1,15 @@
+#![feature(allocator_api)]
+
 use growable::*;
-use std::mem::{align_of, size_of};
+use std::{
+    alloc::{AllocError, Allocator, Global, Layout},
+    cell::Cell,
+    mem::{self, align_of, size_of},
+    ptr::NonNull,
+    rc::Rc,
+    sync::{mpsc, Arc, Barrier},
+    thread,
+};
 
 /// Some sample trait.
 trait Trait {
@@ -26,6 +36,45 @@ impl Trait for Zst {
     }
 }
 
+/// An allocator that keeps track of live allocations and can be asked to fail.
+#[derive(Debug, Clone, Default)]
+struct Counting {
+    live: Rc<Cell<usize>>,
+    total: Rc<Cell<usize>>,
+    fail: Rc<Cell<bool>>,
+}
+
+impl Counting {
+    fn fail(&self, fail: bool) {
+        self.fail.set(fail);
+    }
+
+    fn live(&self) -> usize {
+        self.live.get()
+    }
+
+    fn total(&self) -> usize {
+        self.total.get()
+    }
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        let ptr = Global.allocate(layout)?;
+        self.live.set(self.live.get() + 1);
+        self.total.set(self.total.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
 #[test]
 fn access() {
     // --
@@ -117,7 +166,9 @@ fn access_zst_as_trait() {
 
 #[test]
 fn realloc_with_alignment_change() {
+    #[allow(dead_code)]
     struct Align4(u32);
+    #[allow(dead_code)]
     struct Align8(u64);
     let buffer = Growable::new();
     let a4 = buffer.consume(Align4(0));
@@ -290,3 +341,1431 @@ fn pool_on_demand() {
     pool.free(a);
     assert_eq!(pool.len(), 2);
 }
+
+#[test]
+fn allocator() {
+    let alloc = Counting::default();
+    // --
+    let buffer = Growable::new_in(alloc.clone());
+    assert_eq!(alloc.live(), 0);
+    let v: Reusable<dyn Trait, _> = buffer.consume(StandardType(24));
+    assert_eq!(v.get(), 24);
+    assert_eq!(alloc.live(), 1);
+    // --
+    let buffer = Reusable::free(v);
+    assert_eq!(alloc.live(), 1);
+    let v = replace(buffer.consume(Zst), 7u64);
+    assert_eq!(*v, 7);
+    let (v, buffer) = Reusable::free_move(v);
+    assert_eq!(v, 7);
+    assert_eq!(alloc.live(), 1);
+    mem::drop(buffer);
+    assert_eq!(alloc.live(), 0);
+    assert_eq!(alloc.total(), 2);
+}
+
+#[test]
+fn pool_allocator() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(128)
+        .with_default_ptr_alignment(16)
+        .with_capacity(2)
+        .with_allocator(alloc.clone())
+        .build();
+    assert_eq!(alloc.live(), 2);
+    let a = pool.allocate(1);
+    let b = pool.allocate(2);
+    let c = pool.allocate(3);
+    assert_eq!(alloc.live(), 4);
+    pool.free(a);
+    pool.free(b);
+    mem::drop(c);
+    assert_eq!(alloc.live(), 3);
+    mem::drop(pool);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn try_with_capacity() {
+    let alloc = Counting::default();
+    let buffer = Growable::try_with_capacity_in(16, 8, alloc.clone()).unwrap();
+    assert_eq!(buffer.len(), 16);
+    alloc.fail(true);
+    assert!(Growable::try_with_capacity_in(16, 8, alloc.clone()).is_err());
+    assert!(Growable::try_with_capacity_in(0, 8, alloc.clone()).is_ok());
+    assert_eq!(alloc.live(), 1);
+}
+
+#[test]
+fn try_consume() {
+    let alloc = Counting::default();
+    let buffer = Growable::with_capacity_in(4, 4, alloc.clone());
+    alloc.fail(true);
+    // Fits, no allocation is required.
+    let v = buffer.try_consume(1u32).unwrap();
+    let buffer = Reusable::free(v);
+    // Growing with the same alignment.
+    let (v, buffer) = buffer.try_consume(String::from("Foo")).unwrap_err();
+    assert_eq!(v, "Foo");
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.alignment(), 4);
+    // Reallocating with a different alignment.
+    let (v, buffer) = buffer.try_consume(2u64).unwrap_err();
+    assert_eq!(v, 2);
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.alignment(), 4);
+    assert_eq!(alloc.live(), 1);
+    alloc.fail(false);
+    let v = buffer.try_consume(3u64).unwrap();
+    assert_eq!(*v, 3);
+    assert_eq!(alloc.live(), 1);
+}
+
+#[test]
+fn pool_try_allocate() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(4)
+        .with_default_ptr_alignment(4)
+        .with_capacity(1)
+        .with_allocator(alloc.clone())
+        .build();
+    alloc.fail(true);
+    assert_eq!(pool.try_allocate(2u64).unwrap_err(), 2);
+    assert_eq!(pool.len(), 1);
+    let a = pool.try_allocate(1u32).unwrap();
+    assert_eq!(pool.len(), 0);
+    assert_eq!(pool.try_allocate(2u32).unwrap_err(), 2);
+    assert_eq!(pool.len(), 0);
+    alloc.fail(false);
+    let b = pool.try_allocate(2u64).unwrap();
+    assert_eq!(*b, 2);
+    pool.free(a);
+    pool.free(b);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.live(), 2);
+}
+
+#[test]
+fn try_reuse() {
+    let alloc = Counting::default();
+    let buffer = Growable::new_in(alloc.clone());
+    let (v, buffer) = buffer.try_reuse(1u32).unwrap_err();
+    assert_eq!(v, 1);
+    let buffer = Reusable::free(buffer.try_reuse(Zst).unwrap());
+    let buffer = Reusable::free(buffer.consume(1u32));
+    assert_eq!(alloc.total(), 1);
+    let v = buffer.try_reuse(2u16).unwrap();
+    assert_eq!(*v, 2);
+    let (v, buffer) = Reusable::free(v).try_reuse(3u64).unwrap_err();
+    assert_eq!(v, 3);
+    assert_eq!(buffer.len(), size_of::<u32>());
+    assert_eq!(buffer.alignment(), align_of::<u32>());
+    assert_eq!(alloc.total(), 1);
+}
+
+#[test]
+fn access_zst_with_alignment() {
+    #[repr(align(64))]
+    struct Aligned;
+    let buffer = Growable::new();
+    let v = buffer.consume(Aligned);
+    assert_eq!(&*v as *const Aligned as usize % 64, 0);
+}
+
+#[test]
+fn pool_allocate_no_grow() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(4)
+        .with_default_ptr_alignment(4)
+        .with_capacity(0)
+        .with_allocator(alloc.clone())
+        .build();
+    assert_eq!(pool.allocate_no_grow(1u32).unwrap_err(), 1);
+    let a = pool.allocate([0u64; 2]);
+    let b = pool.allocate(0u32);
+    pool.free(a);
+    pool.free(b);
+    assert_eq!(pool.len(), 2);
+    let total = alloc.total();
+    let a = pool.allocate_no_grow(1u64).unwrap();
+    assert_eq!(*a, 1);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.allocate_no_grow([0u64; 2]).unwrap_err(), [0u64; 2]);
+    let b = pool.allocate_no_grow(2u32).unwrap();
+    assert_eq!(*b, 2);
+    assert_eq!(pool.len(), 0);
+    assert_eq!(alloc.total(), total);
+}
+
+#[test]
+fn growth_policy() {
+    assert_eq!(GrowthPolicy::Exact.round(0), 0);
+    assert_eq!(GrowthPolicy::PowerOfTwo.round(0), 0);
+    assert_eq!(GrowthPolicy::PowerOfTwo.round(129), 256);
+    assert_eq!(GrowthPolicy::PowerOfTwo.round(usize::MAX), usize::MAX);
+    assert_eq!(GrowthPolicy::SizeClasses.round(1), 8);
+    assert_eq!(GrowthPolicy::SizeClasses.round(17), 32);
+    assert_eq!(GrowthPolicy::SizeClasses.round(128), 128);
+    assert_eq!(GrowthPolicy::SizeClasses.round(129), 160);
+    assert_eq!(GrowthPolicy::SizeClasses.round(257), 320);
+    assert_eq!(GrowthPolicy::Step(0).round(7), 7);
+    assert_eq!(GrowthPolicy::Step(10).round(7), 10);
+    // --
+    let alloc = Counting::default();
+    let buffer = Growable::new_in(alloc.clone()).with_growth_policy(GrowthPolicy::PowerOfTwo);
+    let buffer = Reusable::free(buffer.consume([0u8; 5]));
+    assert_eq!(buffer.len(), 8);
+    assert_eq!(buffer.growth_policy(), GrowthPolicy::PowerOfTwo);
+    let buffer = Reusable::free(buffer.consume([0u8; 7]));
+    let buffer = Reusable::free(buffer.consume([0u8; 9]));
+    assert_eq!(buffer.len(), 16);
+    let buffer = Reusable::free(buffer.consume([0u8; 12]));
+    assert_eq!(buffer.len(), 16);
+    assert_eq!(alloc.total(), 2);
+}
+
+#[test]
+fn realloc_keeps_alignment() {
+    #[allow(dead_code)]
+    struct Align8(u64);
+    let buffer = Growable::new();
+    let buffer = Reusable::free(buffer.consume(Align8(0)));
+    assert_eq!(buffer.alignment(), 8);
+    let buffer = Reusable::free(buffer.consume([0u8; 32]));
+    assert_eq!(buffer.len(), 32);
+    assert_eq!(buffer.alignment(), 8);
+}
+
+#[test]
+fn pool_growth_policy() {
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(8)
+        .with_capacity(1)
+        .with_growth_policy(GrowthPolicy::Step(64))
+        .build();
+    let a = pool.allocate([0u8; 10]);
+    let b = Growable::new().consume([0u8; 10]);
+    pool.free(a);
+    pool.free(b);
+    let a = pool.allocate([0u8; 70]);
+    assert_eq!(Reusable::free(a).len(), 128);
+    let b = pool.allocate([0u8; 70]);
+    assert_eq!(Reusable::free(b).len(), 128);
+}
+
+#[test]
+fn consume_with() {
+    let alloc = Counting::default();
+    let buffer = Growable::new_in(alloc.clone());
+    let v = buffer.consume_with(|| [7u8; 4096]);
+    assert!(v.iter().all(|&b| b == 7));
+    let buffer = Reusable::free(v);
+    assert_eq!(buffer.len(), 4096);
+    // --
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        buffer.consume_with::<StandardType, _>(|| panic!("Oops"))
+    }));
+    assert!(result.is_err());
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn consume_uninit() {
+    use std::mem::MaybeUninit;
+    let drop_counter = Rc::new(Cell::new(0));
+    struct Foo(Rc<Cell<usize>>);
+    impl Drop for Foo {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    // Dropping an uninitialized value does nothing.
+    let v = Growable::new().consume_uninit::<Foo>();
+    let buffer = Reusable::free(v);
+    assert_eq!(buffer.len(), size_of::<Foo>());
+    let mut v = buffer.consume_uninit::<Foo>();
+    v.write(Foo(Rc::clone(&drop_counter)));
+    let v = unsafe { Reusable::assume_init(v) };
+    assert_eq!(drop_counter.get(), 0);
+    let buffer = Reusable::free(v);
+    assert_eq!(drop_counter.get(), 1);
+    let v: Reusable<MaybeUninit<Foo>> = buffer.consume_uninit();
+    let v = Reusable::write(v, Foo(Rc::clone(&drop_counter)));
+    mem::drop(v);
+    assert_eq!(drop_counter.get(), 2);
+}
+
+#[test]
+fn pool_allocate_with() {
+    let mut pool = GrowablePoolBuilder::default().with_capacity(1).build();
+    let a = pool.allocate_with(|| [1u16; 512]);
+    assert_eq!(a[511], 1);
+    pool.free(a);
+    let b = pool.allocate_uninit::<[u16; 512]>();
+    let b = Reusable::write(b, [2u16; 512]);
+    assert_eq!(b[0], 2);
+    pool.free(b);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.allocate_with::<u32, _>(|| panic!("Oops"))
+    }));
+    assert!(result.is_err());
+    assert_eq!(pool.len(), 0);
+}
+
+#[test]
+fn consume_box() {
+    let alloc = Counting::default();
+    let b: Box<dyn Trait, _> = Box::new_in(StandardType(24), alloc.clone());
+    let v = Growable::new_in(alloc.clone()).consume_box(b);
+    assert_eq!(v.get(), 24);
+    assert_eq!(alloc.live(), 1);
+    let buffer = Reusable::free(v);
+    assert_eq!(buffer.len(), size_of::<StandardType>());
+    // --
+    let b: Box<dyn Trait> = Box::new(Zst);
+    let v = buffer.consume_box(b);
+    assert_eq!(v.get(), 42);
+    let b: Box<[String]> = vec![String::from("Foo"), String::from("Bar")].into_boxed_slice();
+    let v = Reusable::free(v).consume_box(b);
+    assert_eq!(&*v, &["Foo", "Bar"]);
+}
+
+#[test]
+fn consume_slice() {
+    let names = [String::from("Foo"), String::from("Bar"), String::from("Baz")];
+    let v = Growable::new().consume_slice_clone(&names);
+    assert_eq!(&*v, &names);
+    let buffer = Reusable::free(v);
+    assert_eq!(buffer.len(), size_of::<[String; 3]>());
+    let v = buffer.consume_slice_from_iter([Zst; 16]);
+    assert_eq!(v.len(), 16);
+    let v = Reusable::free(v).consume_str("Hello World");
+    assert_eq!(&*v, "Hello World");
+    let v = Reusable::free(v).consume_str("");
+    assert_eq!(&*v, "");
+}
+
+#[test]
+fn consume_slice_from_iter_panic() {
+    let drop_counter = Rc::new(Cell::new(0));
+    struct Foo(Rc<Cell<usize>>);
+    impl Drop for Foo {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    let alloc = Counting::default();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Growable::new_in(alloc.clone()).consume_slice_from_iter((0 .. 4).map(|i| {
+            if i == 3 {
+                panic!("Oops");
+            }
+            Foo(Rc::clone(&drop_counter))
+        }))
+    }));
+    assert!(result.is_err());
+    assert_eq!(drop_counter.get(), 3);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn pool_allocate_unsized() {
+    let mut pool = GrowablePoolBuilder::default().with_capacity(1).build();
+    let a = pool.allocate_str("Hello World");
+    pool.free(a);
+    let b: Box<dyn Trait> = Box::new(StandardType(12));
+    let b = pool.allocate_box(b);
+    assert_eq!(b.get(), 12);
+    pool.free(b);
+    let c = pool.allocate_slice_clone(&[1, 2, 3]);
+    assert_eq!(&*c, &[1, 2, 3]);
+    pool.free(c);
+    let d = pool.allocate_slice_from_iter(vec![4, 5]);
+    assert_eq!(&*d, &[4, 5]);
+    pool.free(d);
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn box_conversion() {
+    let alloc = Counting::default();
+    let b: Box<dyn Trait, _> = Box::new_in(StandardType(24), alloc.clone());
+    let v = Reusable::from_box(b);
+    assert_eq!(v.get(), 24);
+    assert_eq!(alloc.total(), 1);
+    // Exact fit, no allocation is required.
+    let b = Reusable::into_box(v);
+    assert_eq!(b.get(), 24);
+    assert_eq!(alloc.total(), 1);
+    // The memory does not fit exactly.
+    let v = Growable::with_capacity_in(64, 16, alloc.clone()).consume_str("Hello World");
+    let b = Reusable::into_box(v);
+    assert_eq!(&*b, "Hello World");
+    assert_eq!(alloc.live(), 2);
+    let b: Box<dyn Trait, _> = Box::new_in(Zst, alloc.clone());
+    let b = Reusable::into_box(Reusable::from_box(b));
+    assert_eq!(b.get(), 42);
+    // --
+    let buffer = Growable::from(Box::new(StandardType(24)));
+    assert_eq!(buffer.len(), size_of::<StandardType>());
+}
+
+#[test]
+fn vec_conversion() {
+    let alloc = Counting::default();
+    let mut vec = Vec::with_capacity_in(8, alloc.clone());
+    vec.push(String::from("Foo"));
+    let buffer = Growable::from_vec(vec);
+    assert_eq!(buffer.len(), 8 * size_of::<String>());
+    assert_eq!(buffer.alignment(), align_of::<String>());
+    let mut vec = buffer.into_vec::<u16>();
+    assert_eq!(vec.capacity(), 8 * size_of::<String>() / 2);
+    vec.push(1);
+    assert_eq!(alloc.total(), 2);
+    // --
+    let buffer = Growable::from_vec(vec).into_vec::<[u8; 7]>();
+    assert_eq!(buffer.capacity(), 8 * size_of::<String>() / 7);
+    assert_eq!(alloc.live(), 1);
+    let buffer = Growable::from_vec(buffer).into_vec::<Zst>();
+    assert!(buffer.is_empty());
+    assert_eq!(alloc.live(), 0);
+    let buffer = Growable::from_vec(buffer);
+    assert!(buffer.is_empty());
+    // --
+    let mut pool = GrowablePool::new_in(alloc.clone());
+    pool.free(Vec::<u64, _>::with_capacity_in(4, alloc.clone()));
+    pool.free(buffer);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.live(), 1);
+}
+
+#[test]
+fn reusable_vec() {
+    let alloc = Counting::default();
+    let mut vec = ReusableVec::new_in(alloc.clone());
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), 0);
+    for i in 0 .. 100 {
+        vec.push(i.to_string());
+    }
+    assert_eq!(vec.len(), 100);
+    assert!(vec.capacity() >= 100);
+    assert_eq!(vec[42], "42");
+    assert_eq!(vec.pop().as_deref(), Some("99"));
+    vec.truncate(10);
+    assert_eq!(vec.len(), 10);
+    vec.reserve(100);
+    assert!(vec.capacity() >= 110);
+    assert_eq!(vec.last().map(String::as_str), Some("9"));
+    let clone = vec.clone();
+    assert_eq!(&*clone, &*vec);
+    vec.clear();
+    assert!(vec.is_empty());
+    assert_eq!(alloc.live(), 2);
+    // --
+    let buffer = ReusableVec::free(vec);
+    assert!(buffer.len() >= 110 * size_of::<String>());
+    let v = buffer.consume(StandardType(1));
+    assert_eq!(v.get(), 1);
+    assert_eq!(alloc.live(), 2);
+}
+
+#[test]
+fn reusable_vec_from_growable() {
+    let buffer = Growable::with_capacity(16, 1);
+    let mut vec = ReusableVec::<u32>::from_growable(buffer);
+    // The alignment is not suitable.
+    assert_eq!(vec.capacity(), 0);
+    assert!(vec.is_empty());
+    vec.extend(&[1, 2, 3]);
+    assert_eq!(&*vec, &[1, 2, 3]);
+    let buffer = ReusableVec::free(vec);
+    assert_eq!(buffer.alignment(), align_of::<u32>());
+    let mut vec = ReusableVec::<Zst>::from_growable(buffer);
+    assert_eq!(vec.capacity(), usize::MAX);
+    vec.push(Zst);
+    assert_eq!(vec.len(), 1);
+    // --
+    let mut pool = GrowablePoolBuilder::default().with_capacity(1).build();
+    let mut vec = pool.allocate_vec();
+    vec.extend(0u64 .. 64);
+    pool.free(vec);
+    assert_eq!(pool.len(), 1);
+    let arr = pool.allocate([0u64; 64]);
+    assert_eq!(Reusable::free(arr).len(), 64 * size_of::<u64>());
+}
+
+#[test]
+fn reusable_string() {
+    use std::fmt::Write;
+    let mut s = ReusableString::new();
+    assert!(s.is_empty());
+    s.push_str("Hello");
+    s.push(' ');
+    write!(s, "Wörld, {}!", 42).unwrap();
+    assert_eq!(&*s, "Hello Wörld, 42!");
+    assert_eq!(s.to_string(), "Hello Wörld, 42!");
+    assert_eq!(format!("{:?}", s), "\"Hello Wörld, 42!\"");
+    s.truncate(9);
+    assert_eq!(s.as_str(), "Hello Wö");
+    s.make_ascii_uppercase();
+    assert_eq!(s.as_str(), "HELLO Wö");
+    s.clear();
+    assert!(s.is_empty());
+    assert!(s.capacity() >= 17);
+    let buffer = ReusableString::free(s);
+    assert!(buffer.len() >= 17);
+}
+
+#[test]
+#[should_panic]
+fn reusable_string_truncate_char_boundary() {
+    let mut s = ReusableString::new();
+    s.push_str("ö");
+    s.truncate(1);
+}
+
+#[test]
+fn pool_allocate_string() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(32)
+        .with_default_ptr_alignment(1)
+        .with_capacity(1)
+        .with_allocator(alloc.clone())
+        .build();
+    for i in 0 .. 16 {
+        let mut s = pool.allocate_string();
+        s.push_str("Request #");
+        s.push_str(&i.to_string());
+        assert!(s.starts_with("Request #"));
+        pool.free(s);
+    }
+    assert_eq!(pool.len(), 1);
+    assert_eq!(alloc.total(), 1);
+}
+
+#[test]
+fn pool_selection_policy() {
+    fn build(selection_policy: SelectionPolicy) -> (GrowablePool<Counting>, Counting) {
+        let alloc = Counting::default();
+        let mut pool = GrowablePoolBuilder::default()
+            .with_default_capacity(8)
+            .with_default_ptr_alignment(8)
+            .with_capacity(0)
+            .with_selection_policy(selection_policy)
+            .with_allocator(alloc.clone())
+            .build();
+        let a = pool.allocate([0u8; 64]);
+        let b = pool.allocate([0u8; 16]);
+        let c = pool.allocate([0u8; 32]);
+        pool.free(a);
+        pool.free(b);
+        pool.free(c);
+        (pool, alloc)
+    }
+    // --
+    let (mut pool, _) = build(SelectionPolicy::Lifo);
+    assert_eq!(Reusable::free(pool.allocate(0u8)).len(), 32);
+    let (mut pool, _) = build(SelectionPolicy::Fifo);
+    assert_eq!(Reusable::free(pool.allocate(0u8)).len(), 64);
+    let (mut pool, _) = build(SelectionPolicy::FirstFit);
+    assert_eq!(Reusable::free(pool.allocate([0u8; 48])).len(), 64);
+    let (mut pool, alloc) = build(SelectionPolicy::FirstFit);
+    let total = alloc.total();
+    assert_eq!(Reusable::free(pool.allocate([0u8; 128])).len(), 128);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.total(), total + 1);
+    let (mut pool, _) = build(SelectionPolicy::BestFit);
+    assert_eq!(Reusable::free(pool.allocate([0u8; 20])).len(), 32);
+    let (mut pool, _) = build(SelectionPolicy::BestFit);
+    assert_eq!(Reusable::free(pool.allocate(0u8)).len(), 16);
+    let (mut pool, alloc) = build(SelectionPolicy::BestFit);
+    let total = alloc.total();
+    assert_eq!(Reusable::free(pool.allocate([0u8; 128])).len(), 128);
+    assert_eq!(alloc.total(), total + 1);
+    // The largest block is the one to be regrown.
+    let a = pool.allocate([0u8; 16]);
+    let b = pool.allocate([0u8; 32]);
+    assert_eq!(pool.len(), 0);
+    assert_eq!(Reusable::free(a).len(), 16);
+    assert_eq!(Reusable::free(b).len(), 32);
+}
+
+#[test]
+fn pool_size_classes() {
+    assert_eq!(
+        GrowablePoolBuilder::default().with_size_classes(&[64, 32, 0, 32]),
+        GrowablePoolBuilder::default().with_size_classes(&[32, 64]),
+    );
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(8)
+        .with_default_ptr_alignment(8)
+        .with_capacity(0)
+        .with_size_classes(&[32, 64, 256])
+        .with_allocator(alloc.clone())
+        .build();
+    let small = pool.allocate([0u8; 16]);
+    let large = pool.allocate([0u8; 200]);
+    assert_eq!(alloc.total(), 2);
+    pool.free(small);
+    pool.free(large);
+    assert_eq!(pool.len(), 2);
+    // Goes straight to the matching bucket, no matter the selection policy.
+    let small = Reusable::free(pool.allocate(0u8));
+    assert_eq!(small.len(), 32);
+    pool.free(small);
+    let large = Reusable::free(pool.allocate([0u8; 100]));
+    assert_eq!(large.len(), 256);
+    // Only the matching bucket is refilled.
+    let medium = Reusable::free(pool.allocate([0u8; 48]));
+    assert_eq!(medium.len(), 64);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(alloc.total(), 3);
+    // Everything larger than the largest class goes to the last bucket,
+    // it is refilled with blocks large enough to belong there.
+    let huge = pool.allocate([0u8; 1024]);
+    assert_eq!(alloc.total(), 4);
+    pool.free(huge);
+    let huge = Reusable::free(pool.allocate([0u8; 512]));
+    assert_eq!(huge.len(), 1024);
+    let total = alloc.total();
+    pool.free(medium);
+    pool.free(large);
+    pool.free(huge);
+    assert_eq!(Reusable::free(pool.allocate([0u8; 64])).len(), 64);
+    assert_eq!(Reusable::free(pool.allocate([0u8; 65])).len(), 256);
+    assert_eq!(alloc.total(), total);
+}
+
+#[test]
+fn sync_pool() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<SyncGrowablePool>();
+    // --
+    let pool =
+        Arc::new(GrowablePool::builder().with_capacity(4).enable_overgrow(false).build_shared());
+    let handles: Vec<_> = (0 .. 8)
+        .map(|i| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for j in 0 .. 256usize {
+                    let num = pool.allocate(i * j);
+                    assert_eq!(*num, i * j);
+                    let arr = pool.allocate_slice_clone(&[j; 16]);
+                    assert_eq!(&*arr, &[j; 16]);
+                    pool.free(num);
+                    pool.free(arr);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(pool.len(), 4);
+    let pool = Arc::try_unwrap(pool).unwrap().into_inner();
+    assert_eq!(pool.len(), 4);
+}
+
+#[test]
+fn sync_pool_overgrow() {
+    let pool = Arc::new(GrowablePool::builder().with_capacity(2).build_shared());
+    let barrier = Arc::new(Barrier::new(4));
+    let handles: Vec<_> = (0 .. 4)
+        .map(|i| {
+            let pool = Arc::clone(&pool);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                let num = pool.allocate(i);
+                barrier.wait();
+                pool.free(num);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(pool.len(), 4);
+}
+
+#[test]
+fn sync_pool_cache() {
+    let pool = GrowablePool::builder().with_capacity(4).with_magazine_capacity(2).build_shared();
+    let mut cache = pool.cache();
+    let num = cache.allocate(42u64);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(cache.len(), 2);
+    cache.free(num);
+    cache.free(Growable::new());
+    assert_eq!(pool.len(), 1);
+    assert_eq!(cache.len(), 4);
+    // Both magazines are full, the older one is flushed.
+    cache.free(Growable::new());
+    assert_eq!(pool.len(), 3);
+    assert_eq!(cache.len(), 3);
+    mem::drop(cache);
+    assert_eq!(pool.len(), 6);
+    // Freed on the other thread.
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut cache = pool.cache();
+            for i in 0 .. 64u64 {
+                sender.send(cache.allocate(i)).unwrap();
+            }
+            mem::drop(sender);
+        });
+        scope.spawn(|| {
+            let mut cache = pool.cache();
+            for (i, num) in receiver.into_iter().enumerate() {
+                assert_eq!(*num, i as u64);
+                cache.free(num);
+            }
+        });
+    });
+    assert!(pool.len() >= 6);
+}
+
+#[test]
+fn pooled() {
+    fn parse(pool: &Arc<SyncGrowablePool<Counting>>, s: &str) -> Result<u32, ()> {
+        let s = pool.allocate_pooled(String::from(s));
+        // The block goes back to the pool on the early return as well.
+        let num = s.parse().map_err(|_| ())?;
+        Ok(num)
+    }
+    // --
+    let alloc = Counting::default();
+    #[allow(clippy::arc_with_non_send_sync)]
+    let pool = Arc::new(GrowablePool::builder().with_allocator(alloc.clone()).build_shared());
+    assert_eq!(parse(&pool, "42"), Ok(42));
+    assert_eq!(parse(&pool, "Foo"), Err(()));
+    assert_eq!(pool.len(), 1);
+    assert_eq!(alloc.total(), 2);
+    let value: Pooled<dyn Trait, Counting> = pool.allocate_pooled(StandardType(7));
+    assert_eq!(value.get(), 7);
+    assert!(Arc::ptr_eq(&Pooled::pool(&value).unwrap(), &pool));
+    mem::drop(value);
+    assert_eq!(pool.len(), 1);
+    let num = Pooled::into_reusable(pool.allocate_pooled(0u8));
+    mem::drop(num);
+    assert_eq!(pool.len(), 0);
+    // The pool is gone, the memory is deallocated.
+    let num = pool.allocate_pooled(0u8);
+    assert_eq!(Arc::strong_count(&pool), 1);
+    mem::drop(pool);
+    assert_eq!(alloc.live(), 1);
+    mem::drop(num);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn local_pooled() {
+    use std::cell::RefCell;
+
+    let alloc = Counting::default();
+    let pool = Rc::new(RefCell::new(GrowablePool::builder().with_allocator(alloc.clone()).build()));
+    let value = pool.borrow_mut().allocate(StandardType(7));
+    let value: LocalPooled<dyn Trait, Counting> = LocalPooled::from_reusable(value, &pool);
+    assert_eq!(value.get(), 7);
+    assert!(Rc::ptr_eq(&LocalPooled::pool(&value).unwrap(), &pool));
+    mem::drop(value);
+    assert_eq!(pool.borrow().len(), 1);
+    // The pool is borrowed at the moment, the memory is deallocated.
+    let num = LocalPooled::from_reusable(pool.borrow_mut().allocate(0u8), &pool);
+    let guard = pool.borrow();
+    mem::drop(num);
+    mem::drop(guard);
+    assert_eq!(pool.borrow().len(), 0);
+    let num = LocalPooled::from_reusable(pool.borrow_mut().allocate(0u8), &pool);
+    mem::drop(LocalPooled::into_reusable(num));
+    assert_eq!(alloc.live(), 0);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn pool_stats() {
+    let mut pool = GrowablePool::builder()
+        .with_default_capacity(8)
+        .with_default_ptr_alignment(8)
+        .with_capacity(1)
+        .enable_overgrow(false)
+        .build();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            bytes_held: 8,
+            ..PoolStats::default()
+        }
+    );
+    let a = pool.allocate(0u64);
+    let b = pool.allocate([0u8; 32]);
+    // A regrowth is only recorded once the block is back.
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            allocations: 2,
+            hits: 1,
+            refills: 1,
+            bytes_outstanding: 40,
+            peak_bytes_outstanding: 40,
+            ..PoolStats::default()
+        },
+    );
+    pool.free(a);
+    pool.free(b);
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            allocations: 2,
+            frees: 2,
+            hits: 1,
+            refills: 1,
+            regrowths: 1,
+            dropped: 1,
+            bytes_held: 8,
+            bytes_outstanding: 0,
+            peak_bytes_outstanding: 40,
+        },
+    );
+    pool.reset_stats();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            bytes_held: 8,
+            ..PoolStats::default()
+        }
+    );
+    assert!(pool.allocate_no_grow([0u64; 4]).is_err());
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            bytes_held: 8,
+            ..PoolStats::default()
+        }
+    );
+    // Growing a vector out of the pool counts each reallocation.
+    pool.reset_stats();
+    let mut vec = pool.allocate_vec::<u64>();
+    vec.extend_from_slice(&[0; 16]);
+    vec.reserve(64);
+    pool.free(vec);
+    assert_eq!(pool.stats().regrowths, 2);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn sync_pool_cache_stats() {
+    let pool = GrowablePool::builder()
+        .with_default_capacity(8)
+        .with_capacity(4)
+        .with_magazine_capacity(2)
+        .build_shared();
+    let mut cache = pool.cache();
+    for i in 0 .. 8u64 {
+        let num = cache.allocate(i);
+        cache.free(num);
+    }
+    mem::drop(cache);
+    // A single magazine refill takes three blocks, all of them are back once flushed.
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            allocations: 3,
+            frees: 3,
+            hits: 3,
+            bytes_held: 32,
+            peak_bytes_outstanding: 24,
+            ..PoolStats::default()
+        }
+    );
+    // So is a block regrown through the allocator API.
+    pool.reset_stats();
+    let vec = Vec::<u64, _>::with_capacity_in(2, &pool);
+    mem::drop(vec);
+    assert_eq!(pool.stats().regrowths, 1);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn pool_stats_no_grow_and_cache() {
+    let mut pool = GrowablePool::builder()
+        .with_default_capacity(8)
+        .with_default_ptr_alignment(8)
+        .with_capacity(1)
+        .build();
+    let num = pool.allocate_no_grow(0u64).unwrap();
+    assert_eq!(pool.stats().allocations, 1);
+    assert_eq!(pool.stats().hits, 1);
+    assert_eq!(pool.stats().bytes_outstanding, 8);
+    pool.free(num);
+    // Blocks loaded into a magazine are outstanding with their whole capacity.
+    let pool = GrowablePool::builder().with_capacity(0).with_magazine_capacity(2).build_shared();
+    pool.free(Growable::with_capacity(32, 8));
+    pool.free(Growable::with_capacity(64, 8));
+    pool.free(Growable::with_capacity(16, 8));
+    pool.reset_stats();
+    let mut cache = pool.cache();
+    let num = cache.allocate(0u8);
+    let stats = pool.stats();
+    assert_eq!((stats.allocations, stats.hits), (3, 3));
+    assert_eq!((stats.bytes_outstanding, stats.bytes_held), (112, 0));
+    cache.free(num);
+    mem::drop(cache);
+    let stats = pool.stats();
+    assert_eq!((stats.frees, stats.bytes_outstanding, stats.bytes_held), (3, 0, 112));
+}
+
+#[test]
+fn pool_budget() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_capacity(0)
+        .with_max_bytes(1024)
+        .with_max_growable_len(512)
+        .with_allocator(alloc.clone())
+        .build();
+    pool.free(Growable::with_capacity_in(4096, 8, alloc.clone()));
+    assert_eq!(pool.len(), 1);
+    assert_eq!(alloc.live(), 1);
+    pool.free(Growable::with_capacity_in(256, 8, alloc.clone()));
+    pool.free(Growable::with_capacity_in(512, 8, alloc.clone()));
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.live(), 2);
+    let growable = Reusable::free(pool.allocate([0u8; 300]));
+    assert_eq!(growable.len(), 300);
+    // Fits into the budget again once shrunk.
+    pool.free(Growable::with_capacity_in(768, 8, alloc.clone()));
+    assert_eq!(pool.len(), 2);
+    pool.free(growable);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.live(), 2);
+}
+
+#[test]
+fn pool_trim() {
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_default_capacity(64)
+        .with_capacity(4)
+        .with_allocator(alloc.clone())
+        .build();
+    for len in [128, 256, 512, 1024] {
+        pool.free(Growable::with_capacity_in(len, 8, alloc.clone()));
+    }
+    assert_eq!(alloc.live(), 8);
+    pool.shrink_to_fit();
+    assert_eq!(pool.len(), 4);
+    assert_eq!(alloc.live(), 4);
+    // The least recently returned ones are dropped first.
+    pool.trim_to_bytes(1024 + 512);
+    assert_eq!(pool.len(), 2);
+    pool.trim_to_len(1);
+    assert_eq!(alloc.live(), 1);
+    let growable = Reusable::free(pool.allocate(0u8));
+    assert_eq!(growable.len(), 1024);
+    pool.free(growable);
+    pool.trim_to_len(0);
+    assert!(pool.is_empty());
+    assert_eq!(alloc.live(), 0);
+    // Victims are picked across all buckets at once.
+    let mut pool = GrowablePoolBuilder::default()
+        .with_capacity(0)
+        .with_size_classes(&[64, 256])
+        .with_allocator(alloc.clone())
+        .build();
+    for len in [512, 64, 256, 64, 512, 256] {
+        pool.free(Growable::with_capacity_in(len, 8, alloc.clone()));
+    }
+    pool.trim_to_len(3);
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.bytes(), 64 + 512 + 256);
+    pool.trim_to_bytes(512 + 256);
+    assert_eq!(pool.len(), 2);
+    assert_eq!(alloc.live(), 2);
+}
+
+#[test]
+fn pool_eviction_policy() {
+    fn build(eviction_policy: EvictionPolicy) -> GrowablePool {
+        let mut pool = GrowablePool::builder()
+            .with_capacity(0)
+            .with_max_bytes(1024)
+            .with_eviction_policy(eviction_policy)
+            .build();
+        pool.free(Growable::with_capacity(128, 8));
+        pool.free(Growable::with_capacity(512, 8));
+        pool.free(Growable::with_capacity(256, 8));
+        pool
+    }
+    // --
+    let mut pool = build(EvictionPolicy::DropIncoming);
+    pool.free(Growable::with_capacity(384, 8));
+    assert_eq!((pool.len(), pool.bytes()), (3, 896));
+    let mut pool = build(EvictionPolicy::DropSmallest);
+    pool.free(Growable::with_capacity(384, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 896));
+    // Not worth to drop anything to keep this one.
+    pool.free(Growable::with_capacity(300, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 896));
+    let mut pool = build(EvictionPolicy::DropLargest);
+    pool.free(Growable::with_capacity(384, 8));
+    assert_eq!((pool.len(), pool.bytes()), (3, 768));
+    pool.free(Growable::with_capacity(1024, 8));
+    assert_eq!((pool.len(), pool.bytes()), (3, 768));
+    let mut pool = build(EvictionPolicy::DropLeastRecentlyUsed);
+    pool.free(Growable::with_capacity(384, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 640));
+    pool.free(Growable::with_capacity(512, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 896));
+    // The same goes for a pool capacity with overgrow disabled.
+    let mut pool = GrowablePool::builder()
+        .with_default_capacity(8)
+        .with_capacity(2)
+        .enable_overgrow(false)
+        .with_eviction_policy(EvictionPolicy::DropSmallest)
+        .build();
+    pool.free(Growable::with_capacity(64, 8));
+    pool.free(Growable::with_capacity(32, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 96));
+    pool.free(Growable::with_capacity(16, 8));
+    assert_eq!((pool.len(), pool.bytes()), (2, 96));
+    // A block put back after a failed allocation keeps its age.
+    let alloc = Counting::default();
+    let mut pool = GrowablePoolBuilder::default()
+        .with_capacity(2)
+        .enable_overgrow(false)
+        .with_selection_policy(SelectionPolicy::BestFit)
+        .with_eviction_policy(EvictionPolicy::DropLeastRecentlyUsed)
+        .with_allocator(alloc.clone())
+        .build();
+    pool.free(Growable::with_capacity_in(32, 8, alloc.clone()));
+    pool.free(Growable::with_capacity_in(16, 8, alloc.clone()));
+    alloc.fail(true);
+    assert!(pool.try_allocate([0u64; 8]).is_err());
+    alloc.fail(false);
+    assert_eq!((pool.len(), pool.bytes()), (2, 48));
+    pool.free(Growable::with_capacity_in(16, 8, alloc.clone()));
+    assert_eq!((pool.len(), pool.bytes()), (2, 32));
+}
+
+#[test]
+fn pool_refill_policy() {
+    fn build(refill_policy: RefillPolicy) -> (GrowablePool<Counting>, Counting) {
+        let alloc = Counting::default();
+        let pool = GrowablePoolBuilder::default()
+            .with_capacity(8)
+            .with_refill_policy(refill_policy)
+            .with_allocator(alloc.clone())
+            .build();
+        (pool, alloc)
+    }
+    fn refill(pool: &mut GrowablePool<Counting>) -> usize {
+        pool.trim_to_len(0);
+        let num = pool.allocate(0u8);
+        pool.free(num);
+        pool.len()
+    }
+    // --
+    let (mut pool, alloc) = build(RefillPolicy::Capacity);
+    assert_eq!(alloc.total(), 8);
+    assert_eq!(refill(&mut pool), 8);
+    let (mut pool, _) = build(RefillPolicy::Single);
+    assert_eq!(refill(&mut pool), 1);
+    assert_eq!(refill(&mut pool), 1);
+    let (mut pool, _) = build(RefillPolicy::Batch(3));
+    assert_eq!(refill(&mut pool), 3);
+    let (mut pool, _) = build(RefillPolicy::Geometric(5));
+    assert_eq!(refill(&mut pool), 1);
+    assert_eq!(refill(&mut pool), 2);
+    assert_eq!(refill(&mut pool), 4);
+    assert_eq!(refill(&mut pool), 5);
+    let (mut pool, alloc) = build(RefillPolicy::Manual);
+    assert_eq!(refill(&mut pool), 1);
+    pool.trim_to_len(0);
+    let total = alloc.total();
+    assert_eq!(pool.try_allocate(0u8).unwrap_err(), 0u8);
+    assert_eq!(alloc.total(), total);
+    // Explicit refill.
+    pool.reserve(4);
+    assert_eq!(pool.len(), 4);
+    assert_eq!(alloc.total(), total + 4);
+    pool.reserve(2);
+    assert_eq!(pool.len(), 4);
+    pool.reserve_for::<[u64; 8]>(2);
+    assert_eq!(pool.len(), 6);
+    assert!(pool.allocate_no_grow([0u64; 8]).is_ok());
+    assert!(pool.allocate_no_grow([0u64; 8]).is_ok());
+    assert!(pool.allocate_no_grow([0u64; 8]).is_err());
+    assert_eq!(alloc.total(), total + 6);
+}
+
+#[test]
+fn pool_allocator_api() {
+    let alloc = Counting::default();
+    let pool = GrowablePoolBuilder::default()
+        .with_capacity(1)
+        .with_growth_policy(GrowthPolicy::PowerOfTwo)
+        .with_allocator(alloc.clone())
+        .build_shared();
+    let mut vec = Vec::new_in(&pool);
+    vec.extend(0 .. 100u32);
+    assert_eq!(vec.iter().sum::<u32>(), 4950);
+    mem::drop(vec);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(alloc.live(), 1);
+    // The same block is reused, regrowing it in place whenever possible.
+    let total = alloc.total();
+    let mut vec = Vec::<u32, _>::with_capacity_in(10, &pool);
+    vec.extend(0 .. 100u32);
+    vec.shrink_to_fit();
+    assert_eq!(vec.len(), 100);
+    assert_eq!(alloc.total(), total);
+    let b: Box<dyn Trait, _> = Box::new_in(StandardType(42), &pool);
+    assert_eq!(b.get(), 42);
+    assert_eq!(pool.len(), 0);
+    mem::drop(b);
+    mem::drop(vec);
+    assert_eq!(pool.len(), 2);
+    // Zero-sized allocations never touch the pool.
+    let vec = Vec::<Zst, _>::with_capacity_in(16, &pool);
+    mem::drop(vec);
+    assert_eq!(pool.len(), 2);
+    // Shrinking to zero returns the block back to the pool.
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    let ptr = Allocator::allocate(&&pool, layout).unwrap();
+    assert_eq!(pool.len(), 1);
+    let empty = Layout::from_size_align(0, 8).unwrap();
+    let ptr = unsafe { Allocator::shrink(&&pool, ptr.cast(), layout, empty) }.unwrap();
+    assert_eq!(ptr.len(), 0);
+    assert_eq!(pool.len(), 2);
+    unsafe { Allocator::deallocate(&&pool, ptr.cast(), empty) };
+    assert_eq!(pool.len(), 2);
+    mem::drop(pool);
+    assert_eq!(alloc.live(), 0);
+}
+
+#[test]
+fn pool_allocator_api_failure() {
+    let alloc = Counting::default();
+    let pool = GrowablePoolBuilder::default()
+        .with_capacity(0)
+        .with_allocator(alloc.clone())
+        .build_shared();
+    alloc.fail(true);
+    // Neither the refill nor the regrow aborts, the error is returned instead.
+    let mut vec = Vec::<u32, _>::new_in(&pool);
+    assert!(vec.try_reserve(16).is_err());
+    assert_eq!(pool.len(), 0);
+    alloc.fail(false);
+    vec.reserve(1);
+    alloc.fail(true);
+    assert!(vec.try_reserve(1024).is_err());
+    assert!(Box::try_new_in([0u8; 1024], &pool).is_err());
+    mem::drop(vec);
+    assert_eq!(pool.len(), 1);
+    mem::drop(pool);
+    assert_eq!(alloc.live(), 0);
+
+    // A manual pool is never refilled behind the caller's back.
+    let alloc = Counting::default();
+    let pool = GrowablePoolBuilder::default()
+        .with_capacity(0)
+        .with_refill_policy(RefillPolicy::Manual)
+        .with_allocator(alloc.clone())
+        .build_shared();
+    assert!(Box::try_new_in(0u64, &pool).is_err());
+    assert_eq!(alloc.total(), 0);
+    pool.reserve(1);
+    assert!(Box::try_new_in(0u64, &pool).is_ok());
+    assert_eq!(alloc.total(), 1);
+}
+
+#[test]
+fn local_pool() {
+    /// Recycles a value from the default pool while being dropped.
+    struct Reentrant(Option<Reusable<u32>>);
+    impl Drop for Reentrant {
+        fn drop(&mut self) {
+            if let Some(num) = self.0.take() {
+                Reusable::recycle(num);
+            }
+        }
+    }
+
+    // Runs on its own thread to get a fresh default pool.
+    thread::spawn(|| {
+        GrowablePool::builder().with_capacity(2).make_default();
+        let num = Reusable::new(42);
+        assert_eq!(*num, 42);
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 1);
+        Reusable::recycle(num);
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 2);
+        // Allocating from within the pool falls back to a plain allocation.
+        let num = GrowablePool::with_local(|_| Reusable::new(42));
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 2);
+        Reusable::recycle(num);
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 3);
+        let value = Reusable::new(Reentrant(Some(Reusable::new(42))));
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 1);
+        Reusable::recycle(value);
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 3);
+        let pool = GrowablePool::replace_local(GrowablePool::builder().with_capacity(8).build());
+        assert_eq!(pool.map(|pool| pool.len()), Some(3));
+        assert_eq!(GrowablePool::with_local(|pool| pool.len()), 8);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn pool_scope() {
+    let dropped = Cell::new(0);
+    /// Counts its drops.
+    struct Counted<'a>(&'a Cell<u32>);
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let mut pool = GrowablePool::builder().with_capacity(4).build();
+    let sum = pool.scope(|scope| {
+        let a = scope.allocate(Counted(&dropped));
+        let b = scope.allocate(Counted(&dropped));
+        let c: ScopedReusable<dyn Trait> = scope.allocate(StandardType(42));
+        let d = scope.allocate_str("scoped");
+        assert_eq!(&*d, "scoped");
+        mem::drop(a);
+        mem::forget(b);
+        let e = scope.allocate_with(|| scope.allocate_slice_clone(&[1u32, 2, 3]));
+        let reusable = ScopedReusable::into_reusable(scope.allocate(7u32));
+        e.iter().sum::<u32>() + c.get() + *reusable
+    });
+    assert_eq!(sum, 55);
+    assert_eq!(dropped.get(), 1);
+    // Eight blocks after the refill, the leaked one is back, the detached one is gone.
+    assert_eq!(pool.len(), 7);
+}
+
+#[test]
+fn reusable_rc() {
+    let dropped = Rc::new(Cell::new(0));
+    /// Counts its drops.
+    struct Counted(Rc<Cell<u32>>, u32);
+    impl Trait for Counted {
+        fn get(&self) -> u32 {
+            self.1
+        }
+    }
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let mut pool = GrowablePool::new();
+    let rc: ReusableRc<dyn Trait> = Growable::new().consume_rc(Counted(dropped.clone(), 42));
+    let other = ReusableRc::clone(&rc);
+    let weak = ReusableRc::downgrade(&rc);
+    assert!(ReusableRc::ptr_eq(&rc, &other));
+    assert_eq!(ReusableRc::strong_count(&rc), 2);
+    assert_eq!(ReusableRc::weak_count(&rc), 1);
+    assert_eq!(weak.upgrade().map(|rc| rc.get()), Some(42));
+    assert!(ReusableRc::into_growable(other).is_none());
+    let rc = ReusableRc::try_unwrap(rc).err().unwrap();
+    mem::drop(weak);
+    // The value is moved in front of the header, it is still a `dyn Trait`.
+    let reusable = ReusableRc::try_unwrap(rc).ok().unwrap();
+    assert_eq!(reusable.get(), 42);
+    assert_eq!(dropped.get(), 0);
+    pool.free(reusable);
+    assert_eq!(dropped.get(), 1);
+    assert_eq!(pool.len(), 1);
+
+    let mut rc = Growable::new().consume_rc(Counted(dropped.clone(), 7));
+    ReusableRc::get_mut(&mut rc).unwrap().1 += 1;
+    let weak = ReusableRc::downgrade(&rc);
+    assert!(ReusableRc::get_mut(&mut rc).is_none());
+    assert!(ReusableRc::into_growable(rc).is_none());
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(weak.strong_count(), 0);
+    assert!(weak.upgrade().is_none());
+    mem::drop(weak);
+
+    let shared = Arc::new(SyncGrowablePool::new());
+    let rc = shared.allocate_rc(Counted(dropped.clone(), 1));
+    let weak = ReusableRc::downgrade(&rc);
+    mem::drop(rc);
+    assert_eq!(dropped.get(), 3);
+    assert_eq!(shared.len(), 0);
+    mem::drop(weak);
+    assert_eq!(shared.len(), 1);
+}
+
+#[test]
+fn reusable_arc() {
+    let pool = Arc::new(GrowablePool::builder().with_capacity(1).build_shared());
+    let arc: ReusableArc<dyn Trait + Send + Sync> = pool.allocate_arc(StandardType(42));
+    let threads: Vec<_> = (0 .. 4)
+        .map(|_| {
+            let arc = ReusableArc::clone(&arc);
+            let weak = ReusableArc::downgrade(&arc);
+            thread::spawn(move || {
+                assert_eq!(arc.get(), 42);
+                assert!(weak.upgrade().is_some());
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(ReusableArc::strong_count(&arc), 1);
+    assert_eq!(ReusableArc::weak_count(&arc), 0);
+    assert_eq!(pool.len(), 0);
+    mem::drop(arc);
+    assert_eq!(pool.len(), 1);
+
+    let mut arc = Growable::new().consume_arc(vec![1u32, 2, 3]);
+    ReusableArc::get_mut(&mut arc).unwrap().push(4);
+    let other = ReusableArc::clone(&arc);
+    assert!(ReusableArc::get_mut(&mut arc).is_none());
+    let arc = ReusableArc::try_unwrap(arc).unwrap_err();
+    assert!(ReusableArc::into_growable(other).is_none());
+    let vec = ReusableArc::try_unwrap(arc).unwrap();
+    assert_eq!(*vec, [1, 2, 3, 4]);
+    let growable = ReusableArc::into_growable(Growable::from(vec).consume_arc(0u8)).unwrap();
+    pool.free(growable);
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn reusable_downcast() {
+    use std::any::Any;
+
+    let mut pool = GrowablePool::builder()
+        .with_capacity(1)
+        .with_default_capacity(64)
+        .with_default_ptr_alignment(16)
+        .build();
+    let (tx, rx) = mpsc::channel::<Reusable<dyn Any + Send>>();
+    tx.send(pool.allocate(String::from("message"))).unwrap();
+    let any = rx.recv().unwrap();
+    let any = any.downcast::<u32>().unwrap_err();
+    let message = any.downcast::<String>().unwrap();
+    let (message, growable) = Reusable::free_move(message);
+    assert_eq!(message, "message");
+    assert_eq!(growable.len(), 64);
+    assert_eq!(growable.alignment(), 16);
+    pool.free(growable);
+    assert_eq!(pool.len(), 1);
+
+    let any: Reusable<dyn Any> = pool.allocate(Rc::new(42u32));
+    assert_eq!(**any.downcast::<Rc<u32>>().unwrap(), 42);
+    let any: Reusable<dyn Any + Send + Sync> = pool.allocate(42u64);
+    assert_eq!(*any.downcast::<u64>().unwrap(), 42);
+}
+
+#[test]
+fn reusable_box_traits() {
+    use std::{
+        collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+        error::Error,
+        fmt::{self, Write as _},
+        hash::{Hash, Hasher},
+        io::{self, BufRead, Read, Seek, Write},
+    };
+
+    let mut pool = GrowablePool::default();
+    let mut map = HashMap::new();
+    map.insert(pool.allocate_str("foo"), 1);
+    map.insert(pool.allocate_str("bar"), 2);
+    assert_eq!(map.get("foo"), Some(&1));
+    assert_eq!(map[&pool.allocate_str("bar")], 2);
+    let set: BTreeSet<_> = [3, 1, 2].into_iter().map(|n| pool.allocate(n)).collect();
+    assert_eq!(set.iter().map(|n| **n).collect::<Vec<_>>(), [1, 2, 3]);
+    assert!(pool.allocate(1) < pool.allocate(2));
+    assert!(pool.allocate(2.0) >= pool.allocate(1.0) && pool.allocate(1.0) <= pool.allocate(1.0));
+    let nan = pool.allocate(f64::NAN);
+    assert!(!(nan < pool.allocate(0.0) || nan > pool.allocate(0.0)));
+    assert_eq!(pool.allocate(42).to_string(), "42");
+    let mut name = pool.allocate(String::from("foo"));
+    AsMut::<String>::as_mut(&mut name).push_str("bar");
+    assert_eq!(AsRef::<str>::as_ref(&**name), "foobar");
+
+    let mut iter: Reusable<dyn DoubleEndedIterator<Item = u32>> = pool.allocate(1 .. 5);
+    assert_eq!(iter.next_back(), Some(4));
+    assert_eq!(iter.collect::<Vec<_>>(), [1, 2, 3]);
+
+    let mut cursor: Reusable<io::Cursor<Vec<u8>>> = pool.allocate(io::Cursor::new(Vec::new()));
+    writeln!(cursor, "first").unwrap();
+    cursor.write_all(b"second").unwrap();
+    cursor.rewind().unwrap();
+    let mut line = String::new();
+    cursor.read_line(&mut line).unwrap();
+    assert_eq!(line, "first\n");
+    let mut rest = String::new();
+    cursor.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "second");
+
+    let error: Reusable<dyn Error + Send + Sync> = io::Error::other("oops").into();
+    assert_eq!(error.to_string(), "oops");
+    let error = pool.allocate(fmt::Error);
+    assert!(error.source().is_none());
+
+    let mut hasher = pool.allocate(DefaultHasher::new());
+    42u32.hash(&mut hasher);
+    let mut expected = DefaultHasher::new();
+    42u32.hash(&mut expected);
+    assert_eq!(hasher.finish(), expected.finish());
+    let mut message = String::new();
+    write!(message, "{}", pool.allocate("display")).unwrap();
+    assert_eq!(message, "display");
+}
+
+#[test]
+fn pool_selection_policy_reallocations() {
+    /// Allocates blocks of mixed sizes and returns them in a scrambled but deterministic order,
+    /// returns the amount of allocations made during the warm-up and the following rounds.
+    fn run(selection_policy: SelectionPolicy) -> (usize, usize) {
+        let alloc = Counting::default();
+        let mut pool = GrowablePoolBuilder::default()
+            .with_default_capacity(16)
+            .with_default_ptr_alignment(8)
+            .with_capacity(64)
+            .with_selection_policy(selection_policy)
+            .with_allocator(alloc.clone())
+            .build();
+        let mut buffer = Vec::with_capacity(64);
+        let mut seed = 0usize;
+        let mut round = || {
+            for i in 0 .. 64 {
+                let len = [16, 256, 1024, 4096][i % 4];
+                buffer.push(pool.allocate_slice_from_iter((0 .. len).map(|_| 0u8)));
+            }
+            while !buffer.is_empty() {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let i = (seed >> 33) % buffer.len();
+                pool.free(buffer.swap_remove(i));
+            }
+        };
+        for _ in 0 .. 4 {
+            round();
+        }
+        let warm_up = alloc.total();
+        for _ in 0 .. 4 {
+            round();
+        }
+        (warm_up, alloc.total() - warm_up)
+    }
+    // Blocks only ever grow, the fitting policies settle down sooner and regrow less afterwards.
+    assert_eq!(run(SelectionPolicy::Lifo), (158, 17));
+    assert_eq!(run(SelectionPolicy::Fifo), (156, 14));
+    assert_eq!(run(SelectionPolicy::FirstFit), (125, 4));
+    assert_eq!(run(SelectionPolicy::BestFit), (112, 0));
+}
@@ -0,0 +1,128 @@
+#![cfg(loom)]
+#![feature(allocator_api)]
+
+use growable::*;
+use loom::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Counts its drops.
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn sync_pool_allocate_free() {
+    loom::model(|| {
+        let pool = Arc::new(
+            GrowablePool::builder().with_capacity(2).enable_overgrow(false).build_shared(),
+        );
+        let other = Arc::clone(&pool);
+        let handle = thread::spawn(move || {
+            let num = other.allocate(1u32);
+            other.free(num);
+        });
+        let num = pool.allocate(2u32);
+        assert_eq!(*num, 2);
+        pool.free(num);
+        handle.join().unwrap();
+        assert_eq!(pool.len(), 2);
+    });
+}
+
+#[test]
+fn sync_pool_same_bucket() {
+    loom::model(|| {
+        let pool = Arc::new(
+            GrowablePool::builder()
+                .with_size_classes(&[16, 64])
+                .with_default_capacity(16)
+                .with_capacity(1)
+                .enable_overgrow(false)
+                .build_shared(),
+        );
+        let other = Arc::clone(&pool);
+        // Both threads race for the single block of the 16 bytes bucket, the loser refills it.
+        let handle = thread::spawn(move || {
+            let arr = other.allocate([1u8; 12]);
+            assert_eq!(*arr, [1u8; 12]);
+            other.free(arr);
+        });
+        let arr = pool.allocate([2u8; 12]);
+        assert_eq!(*arr, [2u8; 12]);
+        pool.free(arr);
+        handle.join().unwrap();
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.bytes(), 16);
+    });
+}
+
+#[test]
+fn sync_pool_allocator_api() {
+    loom::model(|| {
+        let pool = Arc::new(GrowablePool::builder().with_capacity(2).build_shared());
+        let other = Arc::clone(&pool);
+        let handle = thread::spawn(move || {
+            let mut vec = Vec::new_in(&*other);
+            vec.extend([1u32, 2, 3]);
+            assert_eq!(vec.iter().sum::<u32>(), 6);
+        });
+        let b = Box::new_in(42u64, &*pool);
+        assert_eq!(*b, 42);
+        drop(b);
+        handle.join().unwrap();
+        assert_eq!(pool.len(), 2);
+    });
+}
+
+#[test]
+fn reusable_arc_release() {
+    loom::model(|| {
+        // NB: The pool hook needs a standard `Arc`, the pool is only reached through it here.
+        let pool = std::sync::Arc::new(
+            GrowablePool::builder().with_capacity(1).enable_overgrow(false).build_shared(),
+        );
+        let arc = pool.allocate_arc(42u32);
+        let weak = ReusableArc::downgrade(&arc);
+        let other = ReusableArc::clone(&arc);
+        let handle = thread::spawn(move || {
+            assert_eq!(*other, 42);
+            if let Some(arc) = weak.upgrade() {
+                assert_eq!(*arc, 42);
+            }
+        });
+        drop(arc);
+        handle.join().unwrap();
+        // The block goes back to the pool exactly once, whichever reference is the last.
+        assert_eq!(pool.len(), 1);
+    });
+}
+
+#[test]
+fn reusable_arc_last_release() {
+    loom::model(|| {
+        let pool = std::sync::Arc::new(
+            GrowablePool::builder().with_capacity(1).enable_overgrow(false).build_shared(),
+        );
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let arc = pool.allocate_arc(Counted(Arc::clone(&dropped)));
+        // Both strong references are released concurrently, neither thread holds the pool.
+        let handles: Vec<_> = [ReusableArc::clone(&arc), arc]
+            .into_iter()
+            .map(|arc| thread::spawn(move || drop(arc)))
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(pool.len(), 1);
+    });
+}